@@ -0,0 +1,33 @@
+use godot::{classes::{IResource, Resource}, prelude::*};
+
+use crate::{campaign_definition::CampaignDefinition, sim::campaign::CampaignList};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct CampaignCatalog {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    campaigns: Array<Gd<CampaignDefinition>>,
+}
+
+#[godot_api]
+impl IResource for CampaignCatalog {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            campaigns: Array::new(),
+        }
+    }
+}
+
+impl CampaignCatalog {
+    pub fn to_list(&self) -> CampaignList {
+        let campaigns = self.campaigns
+            .iter_shared()
+            .filter_map(|definition| definition.bind().to_campaign())
+            .collect();
+        CampaignList { campaigns }
+    }
+}
@@ -0,0 +1,52 @@
+use godot::{classes::{IResource, Resource}, prelude::*};
+
+use crate::{sim::campaign::Campaign, upgrade_definition::to_modifiers};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct CampaignDefinition {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    id: GString,
+    #[export]
+    name: GString,
+    #[export]
+    cost: i32,
+    /// Days it runs, counting the day it was launched.
+    #[export]
+    days: i32,
+    /// Added to a stat by id, e.g. `{"reputation_gain": 0.1}`.
+    #[export]
+    bonuses: Dictionary,
+    /// Multiplies a stat by id, e.g. `{"attraction_student": 1.8}`.
+    #[export]
+    multipliers: Dictionary,
+}
+
+#[godot_api]
+impl IResource for CampaignDefinition {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            id: GString::new(),
+            name: GString::new(),
+            cost: 100,
+            days: 3,
+            bonuses: Dictionary::new(),
+            multipliers: Dictionary::new(),
+        }
+    }
+}
+
+impl CampaignDefinition {
+    pub fn to_campaign(&self) -> Option<Campaign> {
+        if self.id.is_empty() {
+            godot_error!("Campaign {} has no id", self.name);
+            return None;
+        }
+        let modifiers = to_modifiers(&format!("Campaign {}", self.id), &self.bonuses, &self.multipliers)?;
+        Some(Campaign::new(&self.id.to_string(), &self.name.to_string(), self.cost, self.days, modifiers))
+    }
+}
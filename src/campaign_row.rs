@@ -0,0 +1,70 @@
+use godot::{classes::{Button, HBoxContainer, IHBoxContainer, RichTextLabel}, prelude::*};
+
+use crate::sim::{campaign::Campaign, state::GameState, upgrade::Modifier};
+
+#[derive(GodotClass)]
+#[class(base=HBoxContainer)]
+pub struct CampaignRow {
+    base: Base<HBoxContainer>,
+    campaign: String,
+
+    // Change or add your own properties here
+    #[export]
+    name_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    launch_button: Option<Gd<Button>>,
+}
+
+#[godot_api]
+impl IHBoxContainer for CampaignRow {
+    fn init(base: Base<HBoxContainer>) -> Self {
+        Self {
+            base,
+            campaign: String::new(),
+            name_label: None,
+            launch_button: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let launch_button = self.get_launch_button().unwrap();
+        launch_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_launch_pressed);
+    }
+}
+
+#[godot_api]
+impl CampaignRow {
+    #[signal]
+    pub fn launch_requested(id: GString);
+
+    pub fn set_campaign(&mut self, campaign: &Campaign) {
+        self.campaign = campaign.id.clone();
+        let effects: Vec<String> = campaign.modifiers.iter().map(Modifier::describe).collect();
+        self.get_name_label().unwrap().set_text(&format!(
+            "[b]{}[/b] {} days\n{}",
+            campaign.name,
+            campaign.days,
+            effects.join(", "),
+        ));
+    }
+
+    /// Shows the price, or the days left while the campaign runs.
+    pub fn update(&mut self, state: &GameState) {
+        let active = state.campaigns.iter().find(|active| active.id == self.campaign);
+        let (text, disabled) = match (active, state.campaign_list.get(&self.campaign)) {
+            (Some(active), _) => (format!("  {} day(s) left  ", active.days_left), true),
+            (None, Some(campaign)) => (format!("  Launch {}  ", campaign.cost), false),
+            (None, None) => ("  -  ".to_string(), true),
+        };
+        let mut launch_button = self.get_launch_button().unwrap();
+        launch_button.set_text(&text);
+        launch_button.set_disabled(disabled);
+    }
+
+    fn on_launch_pressed(&mut self) {
+        let id = GString::from(self.campaign.as_str());
+        self.signals().launch_requested().emit(&id);
+    }
+}
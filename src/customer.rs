@@ -1,9 +1,11 @@
-use godot::{classes::{AnimatedSprite2D, CharacterBody2D, ICharacterBody2D, Sprite2D, VisibleOnScreenNotifier2D}, global::clampf, prelude::*};
+use godot::{classes::{AnimatedSprite2D, CharacterBody2D, ICharacterBody2D, Label, ProgressBar, Sprite2D, VisibleOnScreenNotifier2D}, prelude::*};
 
-use crate::{customer_variant::CustomerVariant, enums::customer_feedback::CustomerFeedback, singletons::game_data::GameDataSingleton, utils::rng};
+use crate::{customer_variant::CustomerVariant, enums::customer_feedback::CustomerFeedback, queue_manager::QueueManager, sim::{customer::{feedback_for_score, BuyDecision, DrinkChoice, Segment, WalkAwayReason, DEFAULT_PATIENCE}, group, recipe::CoffeeComponent, rng::SimRng}, singletons::game_data::GameDataSingleton, utils::rng::GodotRng};
 
 enum CustomerState {
     Walking,
+    /// Walking to their slot in the line.
+    Queueing,
     Waiting,
     Leaving,
 }
@@ -13,10 +15,31 @@ enum CustomerState {
 pub struct Customer {
     base: Base<CharacterBody2D>,
     pub speed_multiplier: f32,
+    /// The line at the cart, set by the spawner.
+    pub queue: Option<Gd<QueueManager>>,
+    /// Slot in the line they are walking to.
+    queue_slot: Vector2,
+    /// Which way the front of the line is, faced once they're in their slot.
+    queue_front: Vector2,
     visibility_notifier: Option<Gd<VisibleOnScreenNotifier2D>>,
     customer_state: CustomerState,
     walk_direction: Vector2,
     animated_sprite: Option<Gd<AnimatedSprite2D>>,
+    /// Menu index of the drink ordered.
+    drink: Option<usize>,
+    /// Roster name when this is a regular coming back.
+    regular: Option<String>,
+    /// Minutes they wait for their order, from the variant.
+    patience: f32,
+    /// Minutes since they ordered.
+    waited: f32,
+    /// The rest of their group. They walk, wait and leave along with this
+    /// customer, who decides and orders for everyone.
+    followers: Vec<Gd<Customer>>,
+    /// Part of someone else's group.
+    following: bool,
+    /// Cups ordered for the whole group.
+    cups_ordered: i32,
 
     // Change or add your own properties here
     #[export]
@@ -29,6 +52,10 @@ pub struct Customer {
     like_bubble: Option<Gd<Sprite2D>>,
     #[export]
     dislike_bubble: Option<Gd<Sprite2D>>,
+    #[export]
+    name_tag: Option<Gd<Label>>,
+    #[export]
+    patience_bar: Option<Gd<ProgressBar>>,
 }
 
 #[godot_api]
@@ -38,14 +65,26 @@ impl ICharacterBody2D for Customer {
             base,
             walk_speed: 100.0,
             speed_multiplier: 100.0,
+            queue: None,
+            queue_slot: Vector2::ZERO,
+            queue_front: Vector2::RIGHT,
             visibility_notifier: None,
             customer_state: CustomerState::Walking,
             walk_direction: Vector2::RIGHT,
             animated_sprite: None,
+            drink: None,
+            regular: None,
+            patience: DEFAULT_PATIENCE,
+            waited: 0.0,
+            followers: Vec::new(),
+            following: false,
+            cups_ordered: 0,
             variant: None,
             love_bubble: None,
             like_bubble: None,
             dislike_bubble: None,
+            name_tag: None,
+            patience_bar: None,
         }
     }
 
@@ -65,7 +104,8 @@ impl ICharacterBody2D for Customer {
     fn process(&mut self, _delta: f64) {
         match self.customer_state {
             CustomerState::Walking => self.walk(_delta),
-            CustomerState::Waiting => {} // Implement queue behavior here
+            CustomerState::Queueing => self.walk_to_slot(_delta),
+            CustomerState::Waiting => {}
             CustomerState::Leaving => self.walk(_delta),
         }
     }  
@@ -74,7 +114,7 @@ impl ICharacterBody2D for Customer {
 #[godot_api]
 impl Customer {
     #[signal]
-    pub fn on_make_order(customer: Gd<Customer>,amount: i32);
+    pub fn on_make_order(customer: Gd<Customer>,amount: i32, drink: i32);
 
     fn walk(&mut self, delta: f64) {
         let velocity = self.walk_direction * self.walk_speed * delta as f32 * self.speed_multiplier;
@@ -84,6 +124,25 @@ impl Customer {
         customer.move_and_slide();
     }
 
+    fn walk_to_slot(&mut self, delta: f64) {
+        let offset = self.queue_slot - self.base().get_global_position();
+        let speed = self.walk_speed * delta as f32 * self.speed_multiplier;
+        if offset.length() <= (speed * delta as f32).max(0.5) {
+            let slot = self.queue_slot;
+            self.base_mut().set_global_position(slot);
+            self.customer_state = CustomerState::Waiting;
+            self.animated_sprite.as_mut().unwrap().set_animation("idle");
+            self.face(self.queue_front);
+            return;
+        }
+
+        let direction = offset.normalized();
+        self.face(direction);
+        let mut customer = self.base_mut();
+        customer.set_velocity(direction * speed);
+        customer.move_and_slide();
+    }
+
     fn _on_visibility_notifier_screen_exited(&mut self) {
         if let CustomerState::Leaving = self.customer_state {
             self.base_mut().queue_free();
@@ -93,10 +152,92 @@ impl Customer {
 
     pub fn set_walk_direction(&mut self, direction: Vector2) {
         self.walk_direction = direction;
-        if self.walk_direction == Vector2::LEFT {
+        self.face(direction);
+    }
+
+    fn face(&mut self, direction: Vector2) {
+        if direction.x != 0.0 {
             let animated_sprite = self.animated_sprite.as_mut().unwrap();
-            animated_sprite.set_flip_h(true);
+            animated_sprite.set_flip_h(direction.x < 0.0);
+        }
+    }
+
+    /// Makes `followers` this customer's group.
+    pub fn lead(&mut self, followers: Vec<Gd<Customer>>) {
+        for follower in followers.iter() {
+            follower.clone().bind_mut().following = true;
         }
+        self.followers = followers;
+    }
+
+    fn group_size(&self) -> i32 {
+        1 + self.followers.len() as i32
+    }
+
+    /// Sends a customer who ordered to the first of `slots` in the line and
+    /// their group to the others, all facing `front` once there. Customers
+    /// who already left ignore it.
+    pub fn walk_to(&mut self, slots: &[Vector2], front: Vector2) {
+        if !matches!(self.customer_state, CustomerState::Queueing | CustomerState::Waiting) {
+            return;
+        }
+        self.queue_at(slots[0], front);
+        for (follower, slot) in self.followers.iter_mut().zip(slots[1..].iter()) {
+            follower.bind_mut().queue_at(*slot, front);
+        }
+    }
+
+    /// Starts walking to `slot`, whatever they were doing. Followers go
+    /// wherever their leader sends them.
+    fn queue_at(&mut self, slot: Vector2, front: Vector2) {
+        self.queue_slot = slot;
+        self.queue_front = front;
+        self.customer_state = CustomerState::Queueing;
+        self.animated_sprite.as_mut().unwrap().set_animation("default");
+    }
+
+    /// Walks off the way they were going, the group too.
+    fn leave(&mut self) {
+        self.customer_state = CustomerState::Leaving;
+        self.animated_sprite.as_mut().unwrap().set_animation("default");
+        self.face(self.walk_direction);
+        // whoever already walked off screen is gone
+        for follower in self.followers.iter_mut().filter(|follower| follower.is_instance_valid()) {
+            follower.bind_mut().leave();
+        }
+    }
+
+    /// Shows the verdict over everyone in the group.
+    fn show_feedback(&mut self, feedback: CustomerFeedback) {
+        match feedback {
+            CustomerFeedback::Love => self.get_love_bubble().unwrap().set_visible(true),
+            CustomerFeedback::Like => self.get_like_bubble().unwrap().set_visible(true),
+            CustomerFeedback::Dislike => self.get_dislike_bubble().unwrap().set_visible(true),
+            CustomerFeedback::None => (),
+        }
+        for follower in self.followers.iter_mut().filter(|follower| follower.is_instance_valid()) {
+            follower.bind_mut().show_feedback(feedback);
+        }
+    }
+
+    /// Variant of everyone in the group, this customer first.
+    fn group_variants(&self) -> Vec<Gd<CustomerVariant>> {
+        let mut variants = vec![self.get_variant().unwrap()];
+        variants.extend(self.followers.iter().map(|follower| follower.bind().get_variant().unwrap()));
+        variants
+    }
+
+    /// The segment of everyone in the group, the leader first.
+    pub fn group_segments(&self) -> Vec<Segment> {
+        self.group_variants().iter().map(|variant| variant.bind().segment()).collect()
+    }
+
+    /// Marks the customer as a regular from the roster and shows their name.
+    pub fn set_regular(&mut self, name: &str) {
+        self.regular = Some(name.to_string());
+        let mut name_tag = self.get_name_tag().unwrap();
+        name_tag.set_text(name);
+        name_tag.set_visible(true);
     }
 
     #[func]
@@ -104,98 +245,216 @@ impl Customer {
         let gd_self = self.to_gd();
         let body: Gd<Customer> = _body.cast();
         
-        if body != gd_self {
+        // shuffling up the line can bring them back into the cart area, and
+        // groups go with whatever the one in front decides
+        if body != gd_self || self.following || !matches!(self.customer_state, CustomerState::Walking) {
             return;
         }
 
-        let is_buying = self.should_buy();
-        godot_print!("Customer deciding to queue: {}", is_buying);
-        if is_buying {
-            self.customer_state = CustomerState::Waiting;
-            self.animated_sprite.as_mut().unwrap().set_animation("idle");
-            self.make_order();
-        } else {
-            self.customer_state = CustomerState::Leaving;
-            self.animated_sprite.as_mut().unwrap().set_animation("default");
+        let decision = self.should_buy();
+        godot_print!("Customer deciding to queue: {:?}", decision);
+        let lost = match decision {
+            BuyDecision::Stop if self.balks_at_line() => Some(WalkAwayReason::LineTooLong),
+            BuyDecision::Stop => None,
+            BuyDecision::PutOffByPrice => Some(WalkAwayReason::PriceTooHigh),
+            BuyDecision::PassBy => {
+                self.leave();
+                self.remember_visit(CustomerFeedback::None);
+                return;
+            }
+        };
+        if let Some(reason) = lost {
+            for _ in 0..self.group_size() {
+                GameDataSingleton::get_instance().bind_mut().record_choice(&DrinkChoice::WalkAway(reason));
+            }
+            self.leave();
+            self.remember_visit(CustomerFeedback::None);
+            return;
+        }
+
+        self.customer_state = CustomerState::Waiting;
+        self.animated_sprite.as_mut().unwrap().set_animation("idle");
+        self.make_order();
+
+        // they may have walked away from the menu instead
+        if matches!(self.customer_state, CustomerState::Waiting) {
+            let size = self.group_size() as usize;
+            let (slots, front) = self.queue.as_mut().unwrap().bind_mut().join(gd_self, size);
+            self.walk_to(&slots, front);
         }
     }
 
+    /// Looks at the line and decides whether it's worth the wait.
+    fn balks_at_line(&mut self) -> bool {
+        let size = self.group_size() as usize;
+        let queue = self.queue.as_ref().unwrap().bind();
+        if !queue.has_room(size) {
+            godot_print!("Customer balked: no room in line for {}", size);
+            return true;
+        }
+
+        let profile = self.get_variant().unwrap().bind().to_profile();
+        let expected_wait = queue.expected_wait(size);
+        let chance = profile.balk_chance(expected_wait);
+        let roll = GodotRng::new().randf();
+
+        // TODO: comment when not needed
+        godot_print!("=================Join line?===================");
+        godot_print!("In line: {} (+{})", queue.length(), size);
+        godot_print!("Expected wait: {:.0}/{:.0} minutes", expected_wait, profile.patience);
+        godot_print!("Balk chance: {}", chance);
+        godot_print!("RNG roll: {}", roll);
+        godot_print!("==============================================");
+
+        roll < chance
+    }
+
     pub fn make_order(&mut self) {
+        let mut game_data = GameDataSingleton::get_instance();
+        let choice = {
+            let state = &game_data.bind().state;
+            let profile = self.get_variant().unwrap().bind().to_profile().in_weather(state.weather);
+            self.patience = profile.patience;
+            profile.choose_drink(&state.menu, &state.ingredients)
+        };
+        // one cup each, picked by whoever orders
+        for _ in 0..self.group_size() {
+            game_data.bind_mut().record_choice(&choice);
+        }
+
+        let DrinkChoice::Order(drink) = choice else {
+            self.complete_order(None, 0);
+            return;
+        };
+        self.drink = Some(drink);
+        self.cups_ordered = self.group_size();
+        self.waited = 0.0;
+        self.get_patience_bar().unwrap().set_value(1.0);
+        self.get_patience_bar().unwrap().set_visible(true);
+
         let gd_self = self.to_gd();
-        self.signals().on_make_order().emit(&gd_self, 1);
+        let cups = self.cups_ordered;
+        self.signals().on_make_order().emit(&gd_self, cups, drink as i32);
     }
 
-    pub fn complete_order(&mut self, is_bought: bool) -> CustomerFeedback {
-        self.customer_state = CustomerState::Leaving;
-        self.animated_sprite.as_mut().unwrap().set_animation("default");
+    /// Adds `minutes` to their wait and drains the patience meter. Returns
+    /// whether they have had enough.
+    pub fn wait(&mut self, minutes: f32) -> bool {
+        self.waited += minutes;
+        let left = if self.patience > 0.0 { 1.0 - self.waited / self.patience } else { 0.0 };
+        self.get_patience_bar().unwrap().set_value(left.max(0.0) as f64);
+        self.waited >= self.patience
+    }
+
+    /// Leaves the line without their order. Returning their cups and the
+    /// reputation hit are up to the caller, see `GameState::abandon_order`.
+    pub fn abandon(&mut self) {
+        self.leave();
+        self.get_patience_bar().unwrap().set_visible(false);
+        self.show_feedback(CustomerFeedback::Dislike);
+        self.remember_visit(CustomerFeedback::Dislike);
+    }
+
+    /// Sends the customer and their group off, rating the drink as it was
+    /// `served` if they got `cups` of it. The group shares one verdict: the
+    /// average of everyone's score, soured by a long wait or a short order.
+    pub fn complete_order(&mut self, served: Option<CoffeeComponent>, cups: i32) -> CustomerFeedback {
+        self.leave();
+        self.get_patience_bar().unwrap().set_visible(false);
 
-        if !is_bought {
+        let (Some(drink), Some(recipe)) = (self.drink, served) else {
+            self.remember_visit(CustomerFeedback::None);
             return CustomerFeedback::None;
-        }
+        };
 
         let mut game_data = GameDataSingleton::get_instance();
+        let variants = self.group_variants();
         let feedback: CustomerFeedback;
         {
-            let recipe = &game_data.bind().recipe;
-            let coffee_pref = self.get_variant().unwrap().bind().get_coffee_pref();
-            let milk_pref = self.get_variant().unwrap().bind().get_milk_pref();
-            let sugar_pref = self.get_variant().unwrap().bind().get_sugar_pref();
-    
-            let diff_coffee = (recipe.coffee - coffee_pref).abs();
-            let diff_milk = (recipe.milk - milk_pref).abs();
-            let diff_sugar = (recipe.sugar - sugar_pref).abs();
-    
-            let score = 1.0 - ((diff_coffee/10.0 + diff_milk / 150.0 + diff_sugar / 15.0) / 3.0);
-            let score = clampf(score as f64, 0.0, 1.0);
-            
-            if score > 0.85 {
-                let mut love_bubble = self.get_love_bubble().unwrap();
-                love_bubble.set_visible(true);
-                feedback = CustomerFeedback::Love;
-            } else if score > 0.5 {
-                let mut like_bubble = self.get_like_bubble().unwrap();
-                like_bubble.set_visible(true);
-                feedback = CustomerFeedback::Like;
-            } else {
-                let mut dislike_bubble = self.get_dislike_bubble().unwrap();
-                dislike_bubble.set_visible(true);
-                feedback = CustomerFeedback::Dislike;
-            }
+            let state = &game_data.bind().state;
+            let profiles: Vec<_> = variants
+                .iter()
+                .map(|variant| variant.bind().to_profile().in_weather(state.weather))
+                .collect();
+            let score = profiles.iter().map(|profile| profile.score(&recipe, &state.ingredients)).sum::<f32>() / profiles.len() as f32;
+            let profile = &profiles[0];
+            let waited = profile.after_wait(feedback_for_score(score), self.waited);
+            feedback = group::shared_feedback(waited, cups, self.cups_ordered);
 
             // TODO: comment when not needed
             godot_print!("===================Verdict?===================");
-            godot_print!("Coffee/Pref: {}/{}", recipe.coffee, coffee_pref);
-            godot_print!("Milk/Pref: {}/{}", recipe.milk, milk_pref);
-            godot_print!("Sugar/Pref: {}/{}", recipe.sugar, sugar_pref);
+            godot_print!("Drink: {} x{}/{}", state.menu.items[drink].name, cups, self.cups_ordered);
+            for id in state.ingredients.ids() {
+                godot_print!("{}/Pref: {}/{}", state.ingredients.name(id), recipe.get(id), profile.preferences.get(id));
+            }
             godot_print!("Score: {:#?}", score);
+            godot_print!("Waited: {:.0}/{:.0} minutes", self.waited, profile.patience);
             godot_print!("Feedback: {:#?}", feedback);
             godot_print!("==============================================");
         }
+        self.show_feedback(feedback);
 
-        game_data.bind_mut().update_favorability(&feedback);
+        for variant in variants.iter() {
+            let segment = variant.bind().segment();
+            game_data.bind_mut().record_feedback(segment, &feedback);
+        }
+        self.remember_visit(feedback);
         feedback
     }
 
-    fn should_buy(&mut self) -> bool {
+    /// Adds the visit to a regular's history, or lets a happy newcomer join
+    /// the roster.
+    fn remember_visit(&mut self, feedback: CustomerFeedback) {
+        let mut game_data = GameDataSingleton::get_instance();
+        if let Some(name) = &self.regular {
+            game_data.bind_mut().state.record_visit(name, feedback);
+            return;
+        }
+
+        let variant = self.get_variant().unwrap();
+        let segment = variant.bind().segment();
+        let enlisted = game_data
+            .bind_mut()
+            .state
+            .maybe_enlist(&variant.get_path().to_string(), segment, feedback, &mut GodotRng::new());
+        if let Some(name) = enlisted {
+            godot_print!("{} will be back tomorrow", name);
+            self.set_regular(&name);
+        }
+    }
+
+    /// Judges the cheapest drink on sale against what they are willing to pay.
+    fn should_buy(&mut self) -> BuyDecision {
         let game_data = GameDataSingleton::get_instance();
-        
-        let favorability_factor = game_data.bind().favorability;
-        
-        let price = game_data.bind().price;
-        let price_factor = clampf(1.0 - ((price as f64 - 8.0) / 8.0), 0.2, 1.5) as f32;
+        let profile = self.get_variant().unwrap().bind().to_profile();
+        let state = &game_data.bind().state;
 
-        let base_chance = favorability_factor * price_factor;
-        let roll = rng::randf(0.0, 1.0);
+        let Some(price) = state.menu.lowest_price() else {
+            return BuyDecision::PassBy;
+        };
+        let reputation_factor = state.reputation.of(profile.segment);
+        let price_factor = profile.price_factor(price);
+        // regulars who keep coming back are more likely to stop
+        let loyalty = self.regular
+            .as_deref()
+            .and_then(|name| state.roster.get(name))
+            .map(|regular| regular.loyalty());
+        let loyalty_factor = loyalty.map_or(1.0, |loyalty| loyalty.buy_multiplier());
+        let interest = reputation_factor * loyalty_factor;
+        let roll = GodotRng::new().randf();
+        let (decision, buy_chance) = profile.decide(interest, price, state.weather, roll);
 
         // TODO: comment when not needed
         godot_print!("=================Should buy?==================");
-        godot_print!("Favorability factor: {}", favorability_factor);
-        godot_print!("Price factor ({}): {}", price, price_factor);
-        godot_print!("Buy chance: {}", base_chance);
+        godot_print!("Reputation factor ({}): {}", profile.segment.name(), reputation_factor);
+        godot_print!("Price factor ({}, fair {}): {}", price, profile.fair_price, price_factor);
+        godot_print!("Weather factor ({}): {}", state.weather.name(), state.weather.buy_multiplier());
+        godot_print!("Loyalty factor ({}): {}", loyalty.map_or("-", |loyalty| loyalty.name()), loyalty_factor);
+        godot_print!("Buy chance: {}", buy_chance);
         godot_print!("RNG roll: {}", roll);
         godot_print!("==============================================");
 
-        roll < base_chance
+        decision
     }
 
     pub fn on_toggle_fast_forward(&mut self, ff_speed: f64) {
@@ -4,7 +4,10 @@ use godot::{
     prelude::*,
 };
 
-use crate::{customer::Customer, get_node_by_abs_path, selling_phase::SellingPhase, utils::rng};
+use crate::{customer::Customer, get_node_by_abs_path, selling_phase::SellingPhase, sim::{calendar, customer::Segment, group::GroupKind, regulars, rng::SimRng, upgrade::Stat}, singletons::game_data::GameDataSingleton, utils::rng::{self, GodotRng}};
+
+/// Pixels between people walking in a group.
+const GROUP_SPACING: f32 = 10.0;
 
 #[derive(GodotClass)]
 #[class(base=Node2D)]
@@ -14,6 +17,10 @@ struct CustomerSpawner {
     cart_area: Option<Gd<Area2D>>,
     game_manager: Option<Gd<SellingPhase>>,
     starting_speed_multiplier: f32,
+    /// Segment of the variant in each of `customer_scenes`.
+    scene_segments: Vec<Segment>,
+    /// Resource path of the variant in each of `customer_scenes`.
+    scene_variants: Vec<String>,
     
     // Change or add your own properties here
     #[export]
@@ -42,6 +49,8 @@ impl INode2D for CustomerSpawner {
             min_spawn_y: 92.0,
             max_spawn_y: 94.0,
             starting_speed_multiplier: 100.0,
+            scene_segments: Vec::new(),
+            scene_variants: Vec::new(),
         }
     }
 
@@ -49,6 +58,17 @@ impl INode2D for CustomerSpawner {
         self.timer = Some(self.base().get_node_as("Timer"));
         self.cart_area = Some(self.base().get_parent().unwrap().get_node_as("Cart/Area2D"));
         self.game_manager = Some(get_node_by_abs_path!(self.base(), "SellingPhase"));
+        (self.scene_segments, self.scene_variants) = self.get_customer_scenes()
+            .iter_shared()
+            .map(|scene| {
+                let customer = scene.instantiate_as::<Customer>();
+                let variant = customer.bind().get_variant();
+                let segment = variant.as_ref().map_or(Segment::default(), |variant| variant.bind().segment());
+                let path = variant.map_or(String::new(), |variant| variant.get_path().to_string());
+                customer.free();
+                (segment, path)
+            })
+            .unzip();
 
         let timer = self.timer.as_ref().unwrap();
         timer
@@ -68,37 +88,93 @@ impl INode2D for CustomerSpawner {
 #[godot_api]
 impl CustomerSpawner {
     fn spawn_customer(&mut self) {        
-        // decide to spawn
-        let is_spawning = rng::check_chance(self.spawn_chance);
+        let (day, weather, modifiers) = {
+            let game_data = GameDataSingleton::get_instance();
+            let state = &game_data.bind().state;
+            (state.day, state.weather, state.modifiers())
+        };
+
+        // the calendar decides who is out today, campaigns bring more of the segments they target
+        let crowd: Vec<f32> = self.scene_segments
+            .iter()
+            .map(|segment| calendar::segment_multiplier(day, *segment))
+            .collect();
+        let weights: Vec<f32> = self.scene_segments
+            .iter()
+            .zip(crowd.iter())
+            .map(|(segment, weight)| weight * modifiers.value(Stat::SegmentAttraction(*segment)))
+            .collect();
+        let crowd_total: f32 = crowd.iter().sum();
+        let segment_boost = if crowd_total > 0.0 { weights.iter().sum::<f32>() / crowd_total } else { 1.0 };
+
+        // decide to spawn, fewer people are out in bad weather, more on festival days and around a nicer cart
+        let traffic = weather.traffic_multiplier()
+            * calendar::traffic_multiplier(day)
+            * modifiers.value(Stat::Attraction)
+            * segment_boost;
+        let is_spawning = rng::check_chance(self.spawn_chance * traffic);
         if !is_spawning {
             return;
         }
 
-        let i = rng::randi(0, self.get_customer_scenes().len() as i32 - 1);
-        let customer_scene = self.get_customer_scenes().get(i as usize).unwrap();
-
-        // init customer
-        let mut gd_customer = customer_scene
-            .instantiate()
-            .unwrap()
-            .cast::<Customer>();
+        // now and then it's a regular coming back instead of a stranger
+        let regular = if rng::check_chance(regulars::VISIT_CHANCE) {
+            let game_data = GameDataSingleton::get_instance();
+            let state = &game_data.bind().state;
+            state.roster
+                .pick_visitor(day, &self.scene_variants, &mut GodotRng::new())
+                .map(|regular| (regular.name.clone(), regular.variant.clone()))
+        } else {
+            None
+        };
+        let scene_index = match &regular {
+            Some((_, variant)) => self.scene_variants.iter().position(|path| path == variant),
+            None => GodotRng::new().pick_weighted(&weights),
+        };
+        let Some(i) = scene_index else {
+            return;
+        };
+        // some come with friends, colleagues or family, regulars come alone
+        let mut members = vec![i];
+        if regular.is_none() {
+            let kind = GroupKind::of(self.scene_segments[i]);
+            let size = kind.roll_size(&mut GodotRng::new());
+            let company: Vec<f32> = self.scene_segments
+                .iter()
+                .zip(weights.iter())
+                .map(|(segment, weight)| if kind.is_mixed() || *segment == self.scene_segments[i] { *weight } else { 0.0 })
+                .collect();
+            members.extend((1..size).filter_map(|_| GodotRng::new().pick_weighted(&company)));
+            if members.len() > 1 {
+                godot_print!("A group of {} ({}) is coming", members.len(), kind.name());
+            }
+        }
 
-        gd_customer.bind_mut().speed_multiplier = self.starting_speed_multiplier;
-        
-        // set spawn position
-        let i = rng::coin_toss() as usize;
+        // set spawn position, the group walks in a cluster behind whoever leads
+        let side = rng::coin_toss() as usize;
         let spawn_points = self.get_spawn_points();
-        let spawn_point = spawn_points.get(i).unwrap();
+        let spawn_point = spawn_points.get(side).unwrap();
         let y = rng::randf(self.min_spawn_y, self.max_spawn_y);
+        let direction = if side == 1 { Vector2::RIGHT } else { Vector2::LEFT };
         let spawn_coord = Vector2::new(spawn_point.get_position().x, y);
-        gd_customer.set_position(spawn_coord);
-        
-        // spawn customer
-        self.base_mut().add_child(Some(&gd_customer));
-        
-        // set walk direction
+
+        let mut gd_customer = self.add_customer(members[0], spawn_coord, direction);
+        if let Some((name, _)) = &regular {
+            gd_customer.bind_mut().set_regular(name);
+        }
+        let walk_speed = gd_customer.bind().get_walk_speed();
+        let followers: Vec<Gd<Customer>> = members[1..]
+            .iter()
+            .enumerate()
+            .map(|(place, scene)| {
+                let offset = -direction * GROUP_SPACING * (place + 1) as f32 + Vector2::new(0.0, rng::randf(-2.0, 2.0));
+                let mut follower = self.add_customer(*scene, spawn_coord + offset, direction);
+                follower.bind_mut().set_walk_speed(walk_speed);
+                follower
+            })
+            .collect();
         let mut customer = gd_customer.bind_mut();
-        customer.set_walk_direction(if i == 1 { Vector2::RIGHT } else { Vector2::LEFT });
+        customer.lead(followers);
 
         // register cart area signal
         let cart_area = self.cart_area.as_ref().unwrap();
@@ -112,11 +188,32 @@ impl CustomerSpawner {
         customer
             .signals()
             .on_make_order()
-            .connect_other(&*game_manager, SellingPhase::update_orders);
+            .connect_other(game_manager, SellingPhase::update_orders);
+    }
+
+    /// Instantiates customer scene `index` walking in `direction` from `position`.
+    fn add_customer(&mut self, index: usize, position: Vector2, direction: Vector2) -> Gd<Customer> {
+        let customer_scene = self.get_customer_scenes().get(index).unwrap();
+
+        // init customer
+        let mut gd_customer = customer_scene
+            .instantiate()
+            .unwrap()
+            .cast::<Customer>();
+
+        gd_customer.bind_mut().speed_multiplier = self.starting_speed_multiplier;
+        gd_customer.bind_mut().queue = self.game_manager.as_ref().unwrap().bind().get_queue();
+        gd_customer.set_position(position);
 
+        // spawn customer
+        self.base_mut().add_child(Some(&gd_customer));
+        gd_customer.bind_mut().set_walk_direction(direction);
+
+        let game_manager = self.game_manager.as_ref().unwrap();
         game_manager.signals()
             .on_toggle_fast_forward()
-            .connect_other(&*customer, Customer::on_toggle_fast_forward);
+            .connect_other(&*gd_customer.bind(), Customer::on_toggle_fast_forward);
+        gd_customer
     }
 
     #[func]
@@ -1,17 +1,33 @@
 use godot::{classes::{IResource, Resource}, prelude::*};
 
+use crate::sim::{customer::{CustomerProfile, Segment, DEFAULT_ELASTICITY, DEFAULT_PATIENCE, REFERENCE_PRICE}, recipe::CoffeeComponent};
+
 #[derive(GodotClass)]
 #[class(base=Resource)]
 pub struct CustomerVariant {
     base: Base<Resource>,
 
     // Change or add your own properties here
+    /// Preferred amount per ingredient id, ingredients left out don't matter
+    /// to this customer.
+    #[export]
+    preferences: Dictionary,
+    /// Most the customer pays for one cup.
+    #[export]
+    budget: i32,
+    /// Price the customer considers fair for one cup.
     #[export]
-    coffee_pref: f32,
+    fair_price: f32,
+    /// How steeply the customer drops off above the fair price: at 1 a price
+    /// 25% over costs a quarter of them, at 2 half.
     #[export]
-    milk_pref: f32,
+    price_elasticity: f32,
+    /// One of `office_worker`, `student` or `senior`.
     #[export]
-    sugar_pref: f32,
+    segment: GString,
+    /// Minutes of game time the customer waits for their order before giving up.
+    #[export]
+    patience: f32,
 }
 
 #[godot_api]
@@ -19,10 +35,42 @@ impl IResource for CustomerVariant {
     fn init(base: Base<Resource>) -> Self {
         Self {
             base,
-            coffee_pref: 7.0,
-            milk_pref: 120.0,
-            sugar_pref: 10.0,
+            preferences: vdict! {
+                "coffee": 7.0,
+                "milk": 120.0,
+                "sugar": 10.0,
+            },
+            budget: 12,
+            fair_price: REFERENCE_PRICE,
+            price_elasticity: DEFAULT_ELASTICITY,
+            segment: GString::from("office_worker"),
+            patience: DEFAULT_PATIENCE,
         }
     }  
-}     
-        
\ No newline at end of file
+}
+
+impl CustomerVariant {
+    pub fn segment(&self) -> Segment {
+        Segment::from_id(&self.segment.to_string()).unwrap_or_else(|| {
+            godot_error!("Customer variant has unknown segment `{}`", self.segment);
+            Segment::default()
+        })
+    }
+
+    pub fn to_profile(&self) -> CustomerProfile {
+        let mut preferences = CoffeeComponent::default();
+        for (id, amount) in self.preferences.iter_shared() {
+            match amount.try_to::<f32>() {
+                Ok(amount) => preferences.set(&id.to_string(), amount),
+                Err(_) => godot_error!("Customer preference for `{}` is not a number: {}", id, amount),
+            }
+        }
+        CustomerProfile {
+            segment: self.segment(),
+            patience: self.patience,
+            fair_price: self.fair_price,
+            elasticity: self.price_elasticity,
+            ..CustomerProfile::new(preferences, self.budget)
+        }
+    }
+}
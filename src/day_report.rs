@@ -0,0 +1,190 @@
+use godot::{classes::{Button, Control, IControl, RichTextLabel}, prelude::*};
+
+use crate::{sim::{customer::Segment, ingredient::IngredientRegistry, ledger::DayLedger, reputation::Trend, state::PREPARED_CUP}, singletons::game_data::GameDataSingleton};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct DayReport {
+    base: Base<Control>,
+
+    // Change or add your own properties here
+    #[export]
+    title_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    revenue_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    cost_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    expenses_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    profit_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    cups_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    orders_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    staff_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    feedback_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    reputation_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    continue_button: Option<Gd<Button>>,
+}
+
+#[godot_api]
+impl IControl for DayReport {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            title_label: None,
+            revenue_label: None,
+            cost_label: None,
+            expenses_label: None,
+            profit_label: None,
+            cups_label: None,
+            orders_label: None,
+            staff_label: None,
+            feedback_label: None,
+            reputation_label: None,
+            continue_button: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let closed_day = game_data.bind().state.day - 1;
+        let report = game_data.bind().state.ledger.day(closed_day).cloned().unwrap_or_else(|| DayLedger::new(closed_day));
+        let ingredients = game_data.bind().state.ingredients.clone();
+        self.show_report(&report, &ingredients);
+
+        if game_data.bind().state.is_bankrupt() {
+            self.get_title_label().unwrap().set_text(&format!("Day {} - Bankrupt", report.day));
+            self.get_reputation_label().unwrap().set_text(&format!(
+                "You stayed in debt for too long. The run ended with {}.",
+                game_data.bind().state.money,
+            ));
+        }
+
+        let continue_button = self.get_continue_button().unwrap();
+        continue_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_continue);
+    }
+}
+
+impl DayReport {
+    fn show_report(&mut self, report: &DayLedger, ingredients: &IngredientRegistry) {
+        self.get_title_label().unwrap().set_text(&format!("Day {} Report", report.day));
+        self.get_revenue_label().unwrap().set_text(&format!("Revenue: {}", report.revenue()));
+        self.get_cost_label().unwrap().set_text(&format!("Cost of goods: {}", report.cost_of_goods()));
+        let expenses = match report.investments() {
+            0 => format!("Expenses: {}", report.expenses()),
+            invested => format!("Expenses: {} (+{} on upgrades)", report.expenses(), invested),
+        };
+        self.get_expenses_label().unwrap().set_text(&expenses);
+        self.get_profit_label().unwrap().set_text(&format!("Profit: {}", report.profit()));
+        let mut cups = format!(
+            "Cups sold: {} / {} ({} wasted)",
+            report.cups_sold,
+            report.cups_prepared,
+            report.wasted(PREPARED_CUP),
+        );
+        let spoiled: Vec<String> = ingredients.ingredients
+            .iter()
+            .filter(|ingredient| report.wasted(&ingredient.id) > 0.0)
+            .map(|ingredient| format!("{} {} {}", report.wasted(&ingredient.id), ingredient.unit, ingredient.name.to_lowercase()))
+            .collect();
+        if !spoiled.is_empty() {
+            cups.push_str(&format!("\nSpoiled: {}", spoiled.join(", ")));
+        }
+        self.get_cups_label().unwrap().set_text(&cups);
+        self.get_orders_label().unwrap().set_text(&Self::orders_text(report));
+        self.get_staff_label().unwrap().set_text(&Self::staff_text(report));
+        self.get_feedback_label().unwrap().set_text(&format!(
+            "Love {} | Like {} | Dislike {}",
+            report.love,
+            report.like,
+            report.dislike,
+        ));
+        self.get_reputation_label().unwrap().set_text(&Self::reputation_text(report));
+    }
+
+    /// What customers chose and the sales lost, by reason.
+    fn orders_text(report: &DayLedger) -> String {
+        let orders: Vec<String> = report.orders
+            .iter()
+            .map(|(drink, count)| format!("{} {}", drink, count))
+            .collect();
+        let mut text = if orders.is_empty() {
+            "Orders: none".to_string()
+        } else {
+            format!("Orders: {}", orders.join(", "))
+        };
+
+        if report.total_lost_sales() > 0 {
+            let reasons: Vec<String> = report.lost_sales()
+                .iter()
+                .map(|(reason, count)| format!("{} {}", count, reason))
+                .collect();
+            text.push_str(&format!("\nLost sales: {} ({})", report.total_lost_sales(), reasons.join(", ")));
+        }
+        text
+    }
+
+    /// Overall reputation and which segments are growing or churning.
+    fn reputation_text(report: &DayLedger) -> String {
+        let segments: Vec<String> = Segment::ALL
+            .iter()
+            .map(|segment| {
+                let change = report.segment_change(*segment);
+                let text = format!("{} {:.0}%", segment.name(), report.reputation_end.standing(*segment) * 100.0);
+                match Trend::of(change) {
+                    Trend::Steady => format!("{} (steady)", text),
+                    trend => format!("{} ({:+.0}%, {})", text, change * 100.0, trend.name()),
+                }
+            })
+            .collect();
+        format!(
+            "Reputation: {:.0}% -> {:.0}% ({:+.0}%), buzz {:+.0}%\n{}",
+            report.reputation_start.overall() * 100.0,
+            report.reputation_end.overall() * 100.0,
+            report.reputation_change() * 100.0,
+            report.reputation_end.buzz * 100.0,
+            segments.join(" | "),
+        )
+    }
+
+    /// Who served what, how it went down and what they cost.
+    fn staff_text(report: &DayLedger) -> String {
+        let shifts: Vec<String> = report.shifts
+            .iter()
+            .map(|(name, shift)| {
+                let mut text = format!("{} {} cups", name, shift.cups);
+                if let Some(share) = shift.satisfaction() {
+                    text.push_str(&format!(" ({:.0}% satisfied)", share * 100.0));
+                }
+                if shift.wage > 0 {
+                    text.push_str(&format!(", wage {}", shift.wage));
+                }
+                text
+            })
+            .collect();
+        if shifts.is_empty() {
+            "Served: nobody".to_string()
+        } else {
+            format!("Served: {}", shifts.join(" | "))
+        }
+    }
+
+    fn on_continue(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let next_scene = if game_data.bind().state.is_bankrupt() {
+            "res://scenes/main_menu.tscn"
+        } else {
+            "res://scenes/prep_phase.tscn"
+        };
+        let mut tree = self.base().get_tree().unwrap();
+        tree.change_scene_to_file(next_scene);
+    }
+}
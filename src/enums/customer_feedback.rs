@@ -1,7 +1,9 @@
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CustomerFeedback {
     Love,
     Like,
     Dislike,
     None,
-}
\ No newline at end of file
+}
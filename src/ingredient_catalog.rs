@@ -0,0 +1,40 @@
+use godot::{classes::{IResource, Resource, Texture2D}, prelude::*};
+
+use crate::{ingredient_definition::IngredientDefinition, sim::ingredient::IngredientRegistry};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct IngredientCatalog {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    ingredients: Array<Gd<IngredientDefinition>>,
+}
+
+#[godot_api]
+impl IResource for IngredientCatalog {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            ingredients: Array::new(),
+        }
+    }
+}
+
+impl IngredientCatalog {
+    pub fn to_registry(&self) -> IngredientRegistry {
+        let ingredients = self.ingredients
+            .iter_shared()
+            .filter_map(|definition| definition.bind().to_ingredient())
+            .collect();
+        IngredientRegistry { ingredients }
+    }
+
+    pub fn icon(&self, id: &str) -> Option<Gd<Texture2D>> {
+        self.ingredients
+            .iter_shared()
+            .find(|definition| definition.bind().get_id().to_string() == id)
+            .and_then(|definition| definition.bind().get_icon())
+    }
+}
@@ -0,0 +1,56 @@
+use godot::{classes::{IResource, Resource, Texture2D}, prelude::*};
+
+use crate::sim::ingredient::Ingredient;
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct IngredientDefinition {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    /// Key used by recipes, lots, preferences and supplier products.
+    #[export]
+    id: GString,
+    #[export]
+    name: GString,
+    #[export]
+    unit: GString,
+    /// Score lost per unit a recipe is away from a customer's preference.
+    #[export]
+    score_weight: f32,
+    #[export]
+    shelf_life: i32,
+    #[export]
+    icon: Option<Gd<Texture2D>>,
+}
+
+#[godot_api]
+impl IResource for IngredientDefinition {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            id: GString::new(),
+            name: GString::new(),
+            unit: GString::from("g"),
+            score_weight: 0.1,
+            shelf_life: 30,
+            icon: None,
+        }
+    }
+}
+
+impl IngredientDefinition {
+    pub fn to_ingredient(&self) -> Option<Ingredient> {
+        if self.id.is_empty() {
+            godot_error!("Ingredient {} has no id", self.name);
+            return None;
+        }
+        Some(Ingredient::new(
+            &self.id.to_string(),
+            &self.name.to_string(),
+            &self.unit.to_string(),
+            self.score_weight,
+            self.shelf_life,
+        ))
+    }
+}
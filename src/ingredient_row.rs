@@ -0,0 +1,65 @@
+use godot::{classes::{HBoxContainer, IHBoxContainer, LineEdit, RichTextLabel, Texture2D, TextureRect}, prelude::*};
+
+use crate::sim::state::GameState;
+
+#[derive(GodotClass)]
+#[class(base=HBoxContainer)]
+pub struct IngredientRow {
+    base: Base<HBoxContainer>,
+    ingredient: String,
+
+    // Change or add your own properties here
+    #[export]
+    icon_rect: Option<Gd<TextureRect>>,
+    #[export]
+    owned_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    amount_input: Option<Gd<LineEdit>>,
+}
+
+#[godot_api]
+impl IHBoxContainer for IngredientRow {
+    fn init(base: Base<HBoxContainer>) -> Self {
+        Self {
+            base,
+            ingredient: String::new(),
+            icon_rect: None,
+            owned_label: None,
+            amount_input: None,
+        }
+    }
+}
+
+impl IngredientRow {
+    pub fn set_ingredient(&mut self, id: &str, icon: Option<Gd<Texture2D>>) {
+        self.ingredient = id.to_string();
+        if let Some(icon) = icon {
+            self.get_icon_rect().unwrap().set_texture(&icon);
+        }
+    }
+
+    pub fn ingredient(&self) -> &str {
+        &self.ingredient
+    }
+
+    /// Amount per cup entered by the player.
+    pub fn amount(&self) -> f32 {
+        self.get_amount_input().unwrap().get_text().to_float() as f32
+    }
+
+    pub fn set_amount(&mut self, amount: f32) {
+        self.get_amount_input().unwrap().set_text(&format!("{}", amount));
+    }
+
+    /// Shows what is in stock and when the oldest lot goes off.
+    pub fn update(&mut self, state: &GameState) {
+        let id = self.ingredient.as_str();
+        let owned = format!("Owned: {} {}", state.inventory.total(id), state.ingredients.unit(id));
+        let text = match state.inventory.days_until_expiry(id, state.day, &state.ingredients) {
+            Some(1) => format!("{}\nexpires tonight", owned),
+            Some(days) => format!("{}\nexpires in {} days", owned, days),
+            None => owned,
+        };
+        self.get_owned_label().unwrap().set_text(&text);
+    }
+}
@@ -6,16 +6,39 @@ use crate::singletons::game_data::GameDataSingleton;
 mod selling_phase;
 mod customer_spawner;
 mod customer;
+mod queue_manager;
 mod prep_phase;
 mod prepare_tab;
 mod shop_tab;
+mod shop_row;
+mod loans_tab;
 mod customer_variant;
+mod supplier_product;
+mod supplier_catalog;
+mod ingredient_definition;
+mod ingredient_catalog;
+mod ingredient_row;
+mod upgrade_definition;
+mod upgrade_catalog;
+mod upgrade_row;
+mod upgrades_tab;
+mod staff_row;
+mod staff_tab;
+mod campaign_definition;
+mod campaign_catalog;
+mod campaign_row;
+mod marketing_tab;
+mod regulars_tab;
 mod utils;
 mod macros;
 mod singletons;
-mod enums;
+pub mod enums;
 mod error_alert;
 mod main_menu;
+mod day_report;
+mod weather_overlay;
+pub mod save;
+pub mod sim;
 
 struct GdRust;
 
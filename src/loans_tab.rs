@@ -0,0 +1,121 @@
+use godot::{classes::{Button, Control, IControl, LineEdit, RichTextLabel}, prelude::*};
+
+use crate::{error_alert::ErrorAlert, get_node_by_abs_path, sim::state::GameState, singletons::game_data::GameDataSingleton};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct LoansTab {
+    base: Base<Control>,
+    error_alert: Option<Gd<ErrorAlert>>,
+
+    // Change or add your own properties here
+    #[export]
+    credit_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    loans_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    amount_input: Option<Gd<LineEdit>>,
+    #[export]
+    borrow_button: Option<Gd<Button>>,
+    #[export]
+    repay_button: Option<Gd<Button>>,
+}
+
+#[godot_api]
+impl IControl for LoansTab {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            error_alert: None,
+            credit_label: None,
+            loans_label: None,
+            amount_input: None,
+            borrow_button: None,
+            repay_button: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let borrow_button = self.get_borrow_button().unwrap();
+        borrow_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_borrow);
+
+        let repay_button = self.get_repay_button().unwrap();
+        repay_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_repay);
+
+        self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
+        self.update_loans();
+    }
+}
+
+#[godot_api]
+impl LoansTab {
+    #[signal]
+    pub fn on_loan_changed();
+
+    fn on_borrow(&mut self) {
+        let amount = self.get_amount_input().unwrap().get_text().to_int() as i32;
+        let mut game_data = GameDataSingleton::get_instance();
+        let result = game_data.bind_mut().state.take_loan(amount);
+        if let Err(message) = result {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from(&message));
+            return;
+        }
+        self.after_change();
+    }
+
+    fn on_repay(&mut self) {
+        let amount = self.get_amount_input().unwrap().get_text().to_int() as i32;
+        let mut game_data = GameDataSingleton::get_instance();
+        let paid = game_data.bind_mut().state.repay_loans(amount);
+        if paid <= 0 {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from("Nothing to repay"));
+            return;
+        }
+        self.after_change();
+    }
+
+    fn after_change(&mut self) {
+        self.get_amount_input().unwrap().set_text("0");
+        self.update_loans();
+        self.signals().on_loan_changed().emit();
+    }
+
+    fn update_loans(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+        self.get_credit_label().unwrap().set_text(&format!(
+            "Owed: {} | Can borrow: {}",
+            state.outstanding_debt(),
+            state.available_credit(),
+        ));
+        self.get_loans_label().unwrap().set_text(&Self::loans_text(state));
+    }
+
+    fn loans_text(state: &GameState) -> String {
+        if state.loans.is_empty() {
+            return "No loans. Interest is charged daily and installments are collected at closing.".to_string();
+        }
+        state.loans
+            .iter()
+            .enumerate()
+            .map(|(i, loan)| format!(
+                "Loan {}: owes {}, next payment {}, {} day(s) left{}",
+                i + 1,
+                loan.balance,
+                loan.installment(),
+                loan.days_left,
+                match loan.missed_payments {
+                    0 => String::new(),
+                    missed => format!(", {} missed", missed),
+                },
+            ))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
@@ -1,4 +1,4 @@
-use godot::{classes::{Button, Control, IControl, Panel}, prelude::*};
+use godot::{classes::{Button, Control, IControl, ItemList, LineEdit, Panel, Time}, prelude::*};
 
 use crate::singletons::game_data::GameDataSingleton;
 
@@ -6,6 +6,7 @@ use crate::singletons::game_data::GameDataSingleton;
 #[class(base=Control)]
 struct MainMenu {
     base: Base<Control>,
+    slot_ids: Vec<u32>,
 
     // Change or add your own properties here
     #[export]
@@ -20,6 +21,24 @@ struct MainMenu {
     close_credit_button: Option<Gd<Button>>,
     #[export]
     credit_panel: Option<Gd<Panel>>,
+    #[export]
+    load_button: Option<Gd<Button>>,
+    #[export]
+    slot_panel: Option<Gd<Panel>>,
+    #[export]
+    slot_list: Option<Gd<ItemList>>,
+    #[export]
+    slot_name_input: Option<Gd<LineEdit>>,
+    #[export]
+    load_slot_button: Option<Gd<Button>>,
+    #[export]
+    rename_slot_button: Option<Gd<Button>>,
+    #[export]
+    duplicate_slot_button: Option<Gd<Button>>,
+    #[export]
+    delete_slot_button: Option<Gd<Button>>,
+    #[export]
+    close_slot_button: Option<Gd<Button>>,
 }
 
 #[godot_api]
@@ -33,17 +52,31 @@ impl IControl for MainMenu {
             close_credit_button: None,
             exit_button: None,
             credit_panel: None,
+            load_button: None,
+            slot_panel: None,
+            slot_list: None,
+            slot_name_input: None,
+            load_slot_button: None,
+            rename_slot_button: None,
+            duplicate_slot_button: None,
+            delete_slot_button: None,
+            close_slot_button: None,
+            slot_ids: Vec::new(),
         }
     }
 
     fn ready(&mut self) {
-        let game_data = GameDataSingleton::get_instance();
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().import_legacy_save();
         
         self.get_continue_button().unwrap().set_visible(false);
+        self.get_load_button().unwrap().set_visible(false);
         self.get_credit_panel().unwrap().set_visible(false);
+        self.get_slot_panel().unwrap().set_visible(false);
         
         if game_data.bind().is_save_exist() {
             self.get_continue_button().unwrap().set_visible(true);
+            self.get_load_button().unwrap().set_visible(true);
         }
 
         let new_game_button = self.get_new_game_button().unwrap();
@@ -70,6 +103,41 @@ impl IControl for MainMenu {
         continue_button.signals()
             .pressed()
             .connect_other(&*self, Self::load_game);
+
+        let load_button = self.get_load_button().unwrap();
+        load_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::open_slots);
+
+        let close_slot_button = self.get_close_slot_button().unwrap();
+        close_slot_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::close_slots);
+
+        let slot_list = self.get_slot_list().unwrap();
+        slot_list.signals()
+            .item_selected()
+            .connect_other(&*self, Self::on_slot_selected);
+
+        let load_slot_button = self.get_load_slot_button().unwrap();
+        load_slot_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::load_selected_slot);
+
+        let rename_slot_button = self.get_rename_slot_button().unwrap();
+        rename_slot_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::rename_selected_slot);
+
+        let duplicate_slot_button = self.get_duplicate_slot_button().unwrap();
+        duplicate_slot_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::duplicate_selected_slot);
+
+        let delete_slot_button = self.get_delete_slot_button().unwrap();
+        delete_slot_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::delete_selected_slot);
     }
 
     fn process(&mut self, _delta: f64) {
@@ -78,6 +146,9 @@ impl IControl for MainMenu {
 
 impl MainMenu {
     fn new_game(&mut self) {
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().new_game();
+
         let mut tree = self.base().get_tree().unwrap();
         tree.change_scene_to_file("res://scenes/prep_phase.tscn");
     }
@@ -101,5 +172,95 @@ impl MainMenu {
         let mut tree = self.base().get_tree().unwrap();
         tree.change_scene_to_file("res://scenes/prep_phase.tscn");
     }
+
+    fn open_slots(&mut self) {
+        self.refresh_slots();
+        self.get_slot_panel().unwrap().set_visible(true);
+    }
+
+    fn close_slots(&mut self) {
+        self.get_slot_panel().unwrap().set_visible(false);
+
+        let has_slots = !self.slot_ids.is_empty();
+        self.get_continue_button().unwrap().set_visible(has_slots);
+        self.get_load_button().unwrap().set_visible(has_slots);
+    }
+
+    fn refresh_slots(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let slots = game_data.bind().list_slots();
+        let time = Time::singleton();
+
+        let mut slot_list = self.get_slot_list().unwrap();
+        slot_list.clear();
+        for slot in &slots {
+            let last_played = time.get_datetime_string_from_unix_time_ex(slot.meta.last_played)
+                .use_space(true)
+                .done();
+            slot_list.add_item(&format!("{}\n{} | {}", slot.meta.name, slot.meta.summary(), last_played));
+        }
+        self.slot_ids = slots.iter().map(|slot| slot.id).collect();
+        self.get_slot_name_input().unwrap().set_text("");
+    }
+
+    fn selected_slot(&mut self) -> Option<u32> {
+        let selected = self.get_slot_list().unwrap().get_selected_items();
+        let index = selected.as_slice().first().copied()?;
+        self.slot_ids.get(index as usize).copied()
+    }
+
+    fn on_slot_selected(&mut self, index: i64) {
+        let item_text = self.get_slot_list().unwrap().get_item_text(index as i32).to_string();
+        let name = item_text.lines().next().unwrap_or_default();
+        self.get_slot_name_input().unwrap().set_text(name);
+    }
+
+    fn load_selected_slot(&mut self) {
+        let Some(id) = self.selected_slot() else {
+            return;
+        };
+
+        let mut game_data = GameDataSingleton::get_instance();
+        if !game_data.bind_mut().load_slot(id) {
+            return;
+        }
+
+        let mut tree = self.base().get_tree().unwrap();
+        tree.change_scene_to_file("res://scenes/prep_phase.tscn");
+    }
+
+    fn rename_selected_slot(&mut self) {
+        let Some(id) = self.selected_slot() else {
+            return;
+        };
+        let name = self.get_slot_name_input().unwrap().get_text().to_string();
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().rename_slot(id, name);
+        self.refresh_slots();
+    }
+
+    fn duplicate_selected_slot(&mut self) {
+        let Some(id) = self.selected_slot() else {
+            return;
+        };
+
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().duplicate_slot(id);
+        self.refresh_slots();
+    }
+
+    fn delete_selected_slot(&mut self) {
+        let Some(id) = self.selected_slot() else {
+            return;
+        };
+
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().delete_slot(id);
+        self.refresh_slots();
+    }
 }
-        
\ No newline at end of file
@@ -0,0 +1,79 @@
+use godot::{classes::{Control, IControl, VBoxContainer}, prelude::*};
+
+use crate::{campaign_row::CampaignRow, error_alert::ErrorAlert, get_node_by_abs_path, singletons::game_data::GameDataSingleton};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct MarketingTab {
+    base: Base<Control>,
+    error_alert: Option<Gd<ErrorAlert>>,
+    rows: Vec<Gd<CampaignRow>>,
+
+    // Change or add your own properties here
+    #[export]
+    row_scene: Option<Gd<PackedScene>>,
+    #[export]
+    row_container: Option<Gd<VBoxContainer>>,
+}
+
+#[godot_api]
+impl IControl for MarketingTab {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            error_alert: None,
+            rows: Vec::new(),
+            row_scene: None,
+            row_container: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.build_rows();
+        self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
+        self.update_rows();
+    }
+}
+
+#[godot_api]
+impl MarketingTab {
+    #[signal]
+    pub fn on_campaign_launched();
+
+    fn build_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let campaigns = game_data.bind().state.campaign_list.campaigns.clone();
+        let row_scene = self.get_row_scene().unwrap();
+        let mut row_container = self.get_row_container().unwrap();
+
+        for campaign in campaigns.iter() {
+            let mut row = row_scene.instantiate_as::<CampaignRow>();
+            row_container.add_child(&row);
+            row.bind_mut().set_campaign(campaign);
+            row.signals()
+                .launch_requested()
+                .connect_other(&*self, Self::on_launch);
+            self.rows.push(row);
+        }
+    }
+
+    fn on_launch(&mut self, id: GString) {
+        let mut game_data = GameDataSingleton::get_instance();
+        let result = game_data.bind_mut().state.launch_campaign(&id.to_string());
+        if let Err(message) = result {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from(&message));
+            return;
+        }
+        self.update_rows();
+        self.signals().on_campaign_launched().emit();
+    }
+
+    fn update_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+        for row in self.rows.iter_mut() {
+            row.bind_mut().update(state);
+        }
+    }
+}
@@ -1,6 +1,6 @@
-use godot::{classes::{Button, Control, IControl, LineEdit, RichTextLabel}, prelude::*};
+use godot::{classes::{Button, Control, IControl, RichTextLabel}, prelude::*};
 
-use crate::{error_alert::ErrorAlert, shop_tab::ShopTab, singletons::game_data::GameDataSingleton};
+use crate::{error_alert::ErrorAlert, loans_tab::LoansTab, marketing_tab::MarketingTab, shop_tab::ShopTab, upgrades_tab::UpgradesTab, sim::{calendar::{self, Date}, weather::FORECAST_ACCURACY}, singletons::game_data::GameDataSingleton};
 
 
 #[derive(GodotClass)]
@@ -18,10 +18,18 @@ pub struct PrepPhase {
     #[export]
     day_count_label: Option<Gd<RichTextLabel>>,
     #[export]
-    price_input: Option<Gd<LineEdit>>,
+    forecast_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    events_label: Option<Gd<RichTextLabel>>,
     #[export]
     shop_tab: Option<Gd<ShopTab>>,
     #[export]
+    loans_tab: Option<Gd<LoansTab>>,
+    #[export]
+    upgrades_tab: Option<Gd<UpgradesTab>>,
+    #[export]
+    marketing_tab: Option<Gd<MarketingTab>>,
+    #[export]
     error_alert: Option<Gd<ErrorAlert>>,
 }
 
@@ -34,8 +42,12 @@ impl IControl for PrepPhase {
             money_label: None,
             stock_label: None,
             day_count_label: None,
-            price_input: None,
+            forecast_label: None,
+            events_label: None,
             shop_tab: None,
+            loans_tab: None,
+            upgrades_tab: None,
+            marketing_tab: None,
             error_alert: None,
         }
     }
@@ -46,10 +58,19 @@ impl IControl for PrepPhase {
             game_data.bind_mut().start_new();
         }
         
-        let money = game_data.bind().money;
-        let day = game_data.bind().day;
+        let money = game_data.bind().state.money;
+        let day = game_data.bind().state.day;
         self.money_label.as_mut().unwrap().set_text(&money.to_string());
-        self.day_count_label.as_mut().unwrap().set_text(format!("Day {}", day).as_str());
+        let date = Date::of(day);
+        self.day_count_label.as_mut().unwrap().set_text(format!("Day {} - {}", day, date.weekday.short_name()).as_str());
+        let forecast = game_data.bind().state.forecast;
+        self.get_forecast_label().unwrap().set_text(&format!(
+            "{} | Forecast: {} ({:.0}% sure)",
+            date.label(),
+            forecast.name(),
+            FORECAST_ACCURACY,
+        ));
+        self.update_events_label();
 
         let start_day_button = self.start_day_button.as_ref().unwrap();
         start_day_button
@@ -61,6 +82,27 @@ impl IControl for PrepPhase {
         shop_tab.signals()
             .on_buy_success()
             .connect_other(&*self, Self::update_money_label);
+
+        let loans_tab = self.get_loans_tab().unwrap();
+        loans_tab.signals()
+            .on_loan_changed()
+            .connect_other(&*self, Self::update_money_label);
+
+        let upgrades_tab = self.get_upgrades_tab().unwrap();
+        upgrades_tab.signals()
+            .on_upgrade_bought()
+            .connect_other(&*self, Self::update_money_label);
+
+        let marketing_tab = self.get_marketing_tab().unwrap();
+        marketing_tab.signals()
+            .on_campaign_launched()
+            .connect_other(&*self, Self::on_campaign_launched);
+
+        let notice = game_data.bind_mut().take_notice();
+        if let Some(notice) = notice {
+            let mut error_alert = self.get_error_alert().unwrap();
+            error_alert.bind_mut().show_alert(GString::from(notice.as_str()));
+        }
     }
 
     fn process(&mut self, _delta: f64) {
@@ -77,28 +119,58 @@ impl PrepPhase {
 
     fn _on_start_day_button_pressed(&mut self) {
         let mut game_data= GameDataSingleton::get_instance();
-        godot_print!("stock={}", game_data.bind().stock);
-        if game_data.bind().stock <= 0 {
+        godot_print!("stock={}", game_data.bind().state.menu.total_stock());
+        if game_data.bind().state.menu.total_stock() <= 0 {
             let mut error_alert = self.get_error_alert().unwrap();
             error_alert.bind_mut().show_alert(GString::from("Stock are empty please make coffee first"));
             return;
         }
         
-        game_data.bind_mut().price = self.get_price_input().unwrap().get_text().to_int() as i32;
-        if game_data.bind().price <= 0 {
-            let mut error_alert = self.get_error_alert().unwrap();
-            error_alert.bind_mut().show_alert(GString::from("Put a price on your coffee"));
-            return;
-        }
-        
         game_data.bind_mut().start_day();
 
         let mut tree = self.base().get_tree().unwrap();
         tree.change_scene_to_file("res://scenes/selling_phase.tscn");
     }
 
+    fn on_campaign_launched(&mut self) {
+        self.update_money_label();
+        self.update_events_label();
+    }
+
+    /// Special dates in the coming week and the campaigns running.
+    fn update_events_label(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+        let mut text = Self::events_text(state.day);
+        let running: Vec<String> = state.campaigns
+            .iter()
+            .filter_map(|active| state.campaign_list.get(&active.id).map(|campaign| format!("{} ({} day(s) left)", campaign.name, active.days_left)))
+            .collect();
+        if !running.is_empty() {
+            text.push_str(&format!("\nRunning: {}", running.join(", ")));
+        }
+        self.get_events_label().unwrap().set_text(&text);
+    }
+
+    /// Special dates in the coming week.
+    fn events_text(today: i32) -> String {
+        let events: Vec<String> = calendar::upcoming_events(today, calendar::DAYS_PER_WEEK)
+            .into_iter()
+            .map(|(day, event)| match day - today {
+                0 => format!("{} (today)", event.name()),
+                1 => format!("{} (tomorrow)", event.name()),
+                _ => format!("{} ({})", event.name(), Date::of(day).weekday.short_name()),
+            })
+            .collect();
+        if events.is_empty() {
+            "Coming up: nothing special this week".to_string()
+        } else {
+            format!("Coming up: {}", events.join(", "))
+        }
+    }
+
     fn update_money_label(&mut self) {
         let game_data = GameDataSingleton::get_instance();
-        self.get_money_label().unwrap().set_text(&format!("{}", game_data.bind().money));
+        self.get_money_label().unwrap().set_text(&format!("{}", game_data.bind().state.money));
     }
 }
\ No newline at end of file
@@ -1,36 +1,35 @@
-use godot::{classes::{Button, Control, IControl, LineEdit, RichTextLabel}, prelude::*};
+use godot::{classes::{Button, Control, GridContainer, IControl, LineEdit, OptionButton, RichTextLabel}, prelude::*};
 
-use crate::{error_alert::ErrorAlert, get_node_by_abs_path, prep_phase::PrepPhase, shop_tab::ShopTab, singletons::game_data::GameDataSingleton};
+use crate::{error_alert::ErrorAlert, get_node_by_abs_path, ingredient_row::IngredientRow, prep_phase::PrepPhase, shop_tab::ShopTab, upgrades_tab::UpgradesTab, sim::recipe::CoffeeComponent, singletons::game_data::GameDataSingleton};
 
 #[derive(GodotClass)]
 #[class(base=Control)]
 struct PrepareTab {
     base: Base<Control>,
     error_alert: Option<Gd<ErrorAlert>>,
+    rows: Vec<Gd<IngredientRow>>,
 
     // Change or add your own properties here
     #[export]
-    coffee_label: Option<Gd<RichTextLabel>>,
+    drink_select: Option<Gd<OptionButton>>,
     #[export]
-    milk_label: Option<Gd<RichTextLabel>>,
+    row_scene: Option<Gd<PackedScene>>,
     #[export]
-    sugar_label: Option<Gd<RichTextLabel>>,
+    row_container: Option<Gd<GridContainer>>,
     #[export]
     cup_label: Option<Gd<RichTextLabel>>,
     #[export]
-    coffee_input: Option<Gd<LineEdit>>,
-    #[export]
-    milk_input: Option<Gd<LineEdit>>,
-    #[export]
-    sugar_input: Option<Gd<LineEdit>>,
-    #[export]
     price_input: Option<Gd<LineEdit>>,
     #[export]
+    cups_input: Option<Gd<LineEdit>>,
+    #[export]
     save_recipe_button: Option<Gd<Button>>,
     #[export]
     prep_phase: Option<Gd<PrepPhase>>,
     #[export]
     shop_tab: Option<Gd<ShopTab>>,
+    #[export]
+    upgrades_tab: Option<Gd<UpgradesTab>>,
 }
 
 #[godot_api]
@@ -38,17 +37,17 @@ impl IControl for PrepareTab {
     fn init(base: Base<Control>) -> Self {
         Self {
             base,
-            coffee_label: None,
-            milk_label: None,
-            sugar_label: None,
+            rows: Vec::new(),
+            drink_select: None,
+            row_scene: None,
+            row_container: None,
             cup_label: None,
-            coffee_input: None,
-            milk_input: None,
-            sugar_input: None,
             price_input: None,
+            cups_input: None,
             save_recipe_button: None,
             prep_phase: None,
             shop_tab: None,
+            upgrades_tab: None,
             error_alert: None,
         }
     }
@@ -58,18 +57,20 @@ impl IControl for PrepareTab {
         if game_data.bind_mut().is_new_game() {
             game_data.bind_mut().start_new();
         }
-        let inventory = &game_data.bind().inventory;
-        let recipe = &game_data.bind().recipe;
         
-        self.get_coffee_label().unwrap().set_text(&format!("Owned: {} g", inventory.coffee));
-        self.get_milk_label().unwrap().set_text(&format!("Owned: {} mL", inventory.milk));
-        self.get_sugar_label().unwrap().set_text(&format!("Owned: {} g", inventory.sugar));
-        self.get_cup_label().unwrap().set_text(&format!("Owned: {}", game_data.bind().cup));
+        self.build_rows();
+        self.update_inventory();
 
-        self.get_coffee_input().unwrap().set_text(&format!("{}", recipe.coffee));
-        self.get_milk_input().unwrap().set_text(&format!("{}", recipe.milk));
-        self.get_sugar_input().unwrap().set_text(&format!("{}", recipe.coffee));
-        self.get_price_input().unwrap().set_text(&format!("{}", game_data.bind().price));
+        let mut drink_select = self.get_drink_select().unwrap();
+        drink_select.clear();
+        for item in game_data.bind().state.menu.items.iter() {
+            drink_select.add_item(&item.name);
+        }
+        drink_select.signals()
+            .item_selected()
+            .connect_other(&*self, Self::on_drink_selected);
+        drink_select.select(0);
+        self.on_drink_selected(0);
 
         let save_recipe_button = self.save_recipe_button.as_ref().unwrap();
         save_recipe_button
@@ -81,6 +82,11 @@ impl IControl for PrepareTab {
         shop_tab.signals()
             .on_buy_success()
             .connect_other(&*self, Self::update_inventory);
+
+        let upgrades_tab = self.get_upgrades_tab().unwrap();
+        upgrades_tab.signals()
+            .on_upgrade_bought()
+            .connect_other(&*self, Self::update_inventory);
         
         self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
         
@@ -92,23 +98,74 @@ impl IControl for PrepareTab {
 }     
 
 impl PrepareTab {
+    /// One row per ingredient in the catalog, above the cup row.
+    fn build_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let row_scene = self.get_row_scene().unwrap();
+        let mut row_container = self.get_row_container().unwrap();
+        let ids: Vec<String> = game_data.bind().state.ingredients.ids().map(str::to_string).collect();
+
+        for id in ids {
+            let mut row = row_scene.instantiate_as::<IngredientRow>();
+            row_container.add_child(&row);
+            row_container.move_child(&row, self.rows.len() as i32);
+            row.bind_mut().set_ingredient(&id, game_data.bind().ingredient_icon(&id));
+            self.rows.push(row);
+        }
+    }
+
+    fn selected_drink(&self) -> usize {
+        self.get_drink_select().unwrap().get_selected().max(0) as usize
+    }
+
+    /// Loads the drink's recipe, price and prepared cups into the inputs.
+    fn on_drink_selected(&mut self, index: i64) {
+        let game_data = GameDataSingleton::get_instance();
+        let Some(item) = game_data.bind().state.menu.item(index as usize).cloned() else {
+            return;
+        };
+
+        for row in self.rows.iter_mut() {
+            let amount = item.recipe.get(row.bind().ingredient());
+            row.bind_mut().set_amount(amount);
+        }
+        self.get_price_input().unwrap().set_text(&format!("{}", item.price));
+        let cups = match item.stock {
+            0 => String::new(),
+            stock => stock.to_string(),
+        };
+        self.get_cups_input().unwrap().set_text(&cups);
+    }
+
     fn _on_save_recipe_button_pressed(&mut self) {
         let mut game_data = GameDataSingleton::get_instance();
         
         // Save recipe logic here
-        let coffee = self.get_coffee_input().unwrap().get_text().to_float() as f32;
-        let milk = self.get_milk_input().unwrap().get_text().to_float() as f32;
-        let sugar = self.get_sugar_input().unwrap().get_text().to_float() as f32;
+        let mut recipe = CoffeeComponent::default();
+        for row in self.rows.iter() {
+            let row = row.bind();
+            recipe.set(row.ingredient(), row.amount());
+        }
+        let price = self.get_price_input().unwrap().get_text().to_int() as i32;
+        let cups_text = self.get_cups_input().unwrap().get_text();
+        let cups = (!cups_text.is_empty()).then(|| cups_text.to_int() as i32);
 
-        if coffee <= 0.0 && milk <= 0.0 && sugar <= 0.0 {
+        if recipe.iter().any(|(_, amount)| amount < 0.0) || recipe.iter().all(|(_, amount)| amount <= 0.0) {
             let error_alert = self.error_alert.as_mut().unwrap();
             error_alert.bind_mut().show_alert(GString::from("Invalid recipe"));
             return;
         }
+        if price <= 0 {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from("Put a price on your coffee"));
+            return;
+        }
 
-        game_data.bind_mut().save_recipe(coffee, milk, sugar);
+        let drink = self.selected_drink();
+        let prepared = game_data.bind_mut().prepare_drink(drink, recipe, price, cups);
+        self.get_cups_input().unwrap().set_text(&prepared.to_string());
 
-        let stock = game_data.bind().stock;
+        let stock = game_data.bind().state.menu.total_stock();
 
         let prep_phase = self.get_prep_phase();
         prep_phase.unwrap().bind_mut().update_stock(stock);
@@ -116,10 +173,10 @@ impl PrepareTab {
 
     fn update_inventory(&mut self) {
         let game_data = GameDataSingleton::get_instance();
-        let inventory = &game_data.bind().inventory;
-        self.get_coffee_label().unwrap().set_text(&format!("Owned: {} g", inventory.coffee));
-        self.get_milk_label().unwrap().set_text(&format!("Owned: {} mL", inventory.milk));
-        self.get_sugar_label().unwrap().set_text(&format!("Owned: {} g", inventory.sugar));
-        self.get_cup_label().unwrap().set_text(&format!("Owned: {}", game_data.bind().cup));
+        let state = &game_data.bind().state;
+        for row in self.rows.iter_mut() {
+            row.bind_mut().update(state);
+        }
+        self.get_cup_label().unwrap().set_text(&format!("Owned: {}\ncart holds {}", state.cup, state.stock_capacity()));
     }
 }
\ No newline at end of file
@@ -0,0 +1,94 @@
+use godot::{classes::{INode2D, Node2D}, prelude::*};
+
+use crate::customer::Customer;
+
+/// The line in front of the cart. Its own position is the front slot, the
+/// others follow one `slot_spacing` apart along `direction`. A group takes
+/// one slot per person.
+#[derive(GodotClass)]
+#[class(base=Node2D)]
+pub struct QueueManager {
+    base: Base<Node2D>,
+    /// Whoever ordered for each party still waiting and how many they are,
+    /// front first.
+    line: Vec<(Gd<Customer>, usize)>,
+    /// Minutes the counter takes per cup with everybody working, set by the
+    /// selling phase.
+    minutes_per_cup: f32,
+
+    // Change or add your own properties here
+    #[export]
+    slot_spacing: f32,
+    #[export]
+    max_length: i32,
+    /// From the front of the line towards its end.
+    #[export]
+    direction: Vector2,
+}
+
+#[godot_api]
+impl INode2D for QueueManager {
+    fn init(base: Base<Node2D>) -> Self {
+        Self {
+            base,
+            line: Vec::new(),
+            minutes_per_cup: 0.0,
+            slot_spacing: 14.0,
+            max_length: 7,
+            direction: Vector2::LEFT,
+        }
+    }
+}
+
+#[godot_api]
+impl QueueManager {
+    /// People in line, groups counted in full.
+    pub fn length(&self) -> usize {
+        self.line.iter().map(|(_, size)| size).sum()
+    }
+
+    pub fn has_room(&self, size: usize) -> bool {
+        self.length() + size <= self.max_length as usize
+    }
+
+    pub fn set_minutes_per_cup(&mut self, minutes: f32) {
+        self.minutes_per_cup = minutes;
+    }
+
+    /// Minutes a party of `size` joining now would wait for their cups.
+    pub fn expected_wait(&self, size: usize) -> f32 {
+        (self.length() + size) as f32 * self.minutes_per_cup
+    }
+
+    /// Puts the party at the end of the line. Returns the slots they should
+    /// stand in and which way is the front.
+    pub fn join(&mut self, customer: Gd<Customer>, size: usize) -> (Vec<Vector2>, Vector2) {
+        let first = self.length();
+        self.line.push((customer, size));
+        (self.slot_positions(first, size), -self.direction)
+    }
+
+    /// Takes the party out of the line and moves everyone behind them up.
+    pub fn leave(&mut self, customer: &Gd<Customer>) {
+        let Some(index) = self.line.iter().position(|(other, _)| other == customer) else {
+            return;
+        };
+        self.line.remove(index);
+
+        let front = -self.direction;
+        let mut first: usize = self.line[..index].iter().map(|(_, size)| size).sum();
+        for entry in index..self.line.len() {
+            let size = self.line[entry].1;
+            let slots = self.slot_positions(first, size);
+            self.line[entry].0.bind_mut().walk_to(&slots, front);
+            first += size;
+        }
+    }
+
+    fn slot_positions(&self, first: usize, count: usize) -> Vec<Vector2> {
+        let origin = self.base().get_global_position();
+        (first..first + count)
+            .map(|slot| origin + self.direction.normalized() * self.slot_spacing * slot as f32)
+            .collect()
+    }
+}
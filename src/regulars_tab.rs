@@ -0,0 +1,65 @@
+use godot::{classes::{Control, IControl, RichTextLabel}, prelude::*};
+
+use crate::{sim::regulars::{Regular, MAX_REGULARS}, singletons::game_data::GameDataSingleton};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct RegularsTab {
+    base: Base<Control>,
+
+    // Change or add your own properties here
+    #[export]
+    roster_label: Option<Gd<RichTextLabel>>,
+}
+
+#[godot_api]
+impl IControl for RegularsTab {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            roster_label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.update_roster();
+    }
+}
+
+#[godot_api]
+impl RegularsTab {
+    /// Active regulars first, most loyal on top, then the ones who stopped coming.
+    fn update_roster(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+
+        let mut active: Vec<&Regular> = state.roster.active().collect();
+        active.sort_by_key(|regular| std::cmp::Reverse(regular.happy_visits()));
+        let lapsed: Vec<&Regular> = state.roster.regulars.iter().filter(|regular| regular.lapsed).collect();
+
+        let mut lines = vec![format!("Regulars {}/{}", active.len(), MAX_REGULARS)];
+        if state.roster.regulars.is_empty() {
+            lines.push("Nobody yet. Customers who love their cup may come back.".to_string());
+        }
+        lines.extend(active.iter().map(|regular| Self::describe(regular)));
+        if !lapsed.is_empty() {
+            lines.push(String::new());
+            lines.push("Stopped coming".to_string());
+            lines.extend(lapsed.iter().map(|regular| Self::describe(regular)));
+        }
+        self.get_roster_label().unwrap().set_text(&lines.join("\n"));
+    }
+
+    /// "Maya (Students) - Loyal, 9 visits, last on day 12, satisfaction growing"
+    fn describe(regular: &Regular) -> String {
+        format!(
+            "{} ({}) - {}, {} visit(s), last on day {}, satisfaction {}",
+            regular.name,
+            regular.segment.name(),
+            regular.loyalty().name(),
+            regular.visits.len(),
+            regular.last_visit().unwrap_or(regular.joined_on),
+            regular.satisfaction_trend().name(),
+        )
+    }
+}
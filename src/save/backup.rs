@@ -0,0 +1,75 @@
+//! Crash-safe writes and the backup ring kept next to each slot.
+//!
+//! A save is written to a temporary file first and renamed over the slot
+//! once it is complete. Before that, the previous good save is copied into
+//! the backup folder, keeping the newest [`MAX_BACKUPS`] per slot.
+use std::cmp::Reverse;
+
+pub const MAX_BACKUPS: usize = 3;
+
+const BACKUP_EXTENSION: &str = ".bak";
+
+pub fn temp_path(path: &str) -> String {
+    format!("{}.tmp", path)
+}
+
+pub fn backup_file_name(slot_id: u32, timestamp_msec: u64) -> String {
+    format!("slot_{}.{}{}", slot_id, timestamp_msec, BACKUP_EXTENSION)
+}
+
+/// Splits a backup file name into its slot id and timestamp.
+pub fn parse_backup_file_name(file_name: &str) -> Option<(u32, u64)> {
+    let (slot_id, timestamp) = file_name
+        .strip_prefix("slot_")?
+        .strip_suffix(BACKUP_EXTENSION)?
+        .split_once('.')?;
+    Some((slot_id.parse().ok()?, timestamp.parse().ok()?))
+}
+
+/// Backups of `slot_id` among `file_names`, newest first.
+pub fn backups_of(slot_id: u32, file_names: &[String]) -> Vec<String> {
+    let mut backups: Vec<(u64, &String)> = file_names
+        .iter()
+        .filter_map(|file_name| match parse_backup_file_name(file_name) {
+            Some((id, timestamp)) if id == slot_id => Some((timestamp, file_name)),
+            _ => None,
+        })
+        .collect();
+    backups.sort_by_key(|(timestamp, _)| Reverse(*timestamp));
+    backups.into_iter().map(|(_, file_name)| file_name.clone()).collect()
+}
+
+/// Backups of `slot_id` that fall outside the ring and should be deleted.
+pub fn backups_to_prune(slot_id: u32, file_names: &[String]) -> Vec<String> {
+    backups_of(slot_id, file_names).into_iter().skip(MAX_BACKUPS).collect()
+}
+
+#[test]
+fn backup_file_names() {
+    // Execute test
+    assert_eq!(backup_file_name(2, 1_700_000_000_123), "slot_2.1700000000123.bak");
+    assert_eq!(parse_backup_file_name("slot_2.1700000000123.bak"), Some((2, 1_700_000_000_123)));
+    assert_eq!(parse_backup_file_name("slot_2.json"), None);
+    assert_eq!(parse_backup_file_name("slot_2.abc.bak"), None);
+}
+
+#[test]
+fn prune_keeps_newest_backups() {
+    // Test data
+    let file_names: Vec<String> = [
+        "slot_1.100.bak",
+        "slot_1.400.bak",
+        "slot_2.500.bak",
+        "slot_1.300.bak",
+        "slot_1.200.bak",
+        "notes.txt",
+    ]
+    .iter()
+    .map(|name| name.to_string())
+    .collect();
+
+    // Execute test
+    assert_eq!(backups_of(1, &file_names), vec!["slot_1.400.bak", "slot_1.300.bak", "slot_1.200.bak", "slot_1.100.bak"]);
+    assert_eq!(backups_to_prune(1, &file_names), vec!["slot_1.100.bak"]);
+    assert!(backups_to_prune(2, &file_names).is_empty());
+}
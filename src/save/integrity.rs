@@ -0,0 +1,129 @@
+//! Checksummed save envelope.
+//!
+//! Saves are written as `{ "version", "checksum", "payload" }` where the
+//! checksum covers the save version and the serialized payload. It won't stop
+//! a determined player, but it keeps hand-edited saves out of balance tests.
+use serde_json::{json, Value};
+
+use crate::save::migrations;
+
+/// Mixed into every checksum so a plain hash of the payload doesn't match.
+const CHECKSUM_SALT: &str = "cafe-on-wheels";
+/// First save version that was never written without an envelope.
+pub const SEALED_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveIntegrity {
+    Ok,
+    /// Readable, but the checksum doesn't match what the game wrote.
+    Modified,
+    /// Not a save this game can read at all.
+    Corrupt,
+}
+
+pub fn checksum(version: u32, payload: &Value) -> String {
+    // FNV-1a, 64 bit
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let input = format!("{}:{}:{}", CHECKSUM_SALT, version, payload);
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// Wraps a payload of save `version` into a checksummed envelope.
+pub fn seal(version: u32, payload: Value) -> Value {
+    json!({
+        "version": version,
+        "checksum": checksum(version, &payload),
+        "payload": payload,
+    })
+}
+
+/// Unwraps an envelope and reports whether it was tampered with.
+///
+/// Saves written before [`SEALED_VERSION`] have no checksum; they are
+/// returned as they are and trusted. A newer save without an envelope had it
+/// stripped, so it counts as modified.
+pub fn open(mut value: Value) -> (SaveIntegrity, Value) {
+    let Some(envelope) = value.as_object_mut() else {
+        return (SaveIntegrity::Corrupt, value);
+    };
+    let Some(payload) = envelope.remove("payload") else {
+        let integrity = match migrations::version_of(&value) {
+            Ok(version) if version >= SEALED_VERSION => SaveIntegrity::Modified,
+            Ok(_) => SaveIntegrity::Ok,
+            Err(_) => SaveIntegrity::Corrupt,
+        };
+        return (integrity, value);
+    };
+
+    let Ok(version) = migrations::version_of(&payload) else {
+        return (SaveIntegrity::Corrupt, payload);
+    };
+    let stored_checksum = envelope.get("checksum").and_then(Value::as_str);
+    let stored_version = envelope.get("version").and_then(Value::as_u64);
+
+    let integrity = if stored_version == Some(version as u64)
+        && stored_checksum == Some(checksum(version, &payload).as_str())
+    {
+        SaveIntegrity::Ok
+    } else {
+        SaveIntegrity::Modified
+    };
+    (integrity, payload)
+}
+
+#[test]
+fn sealed_save_opens_ok() {
+    // Test data
+    let payload = json!({"version": 1, "money": 300});
+
+    // Execute test
+    let (integrity, opened) = open(seal(1, payload.clone()));
+    assert_eq!(integrity, SaveIntegrity::Ok);
+    assert_eq!(opened, payload);
+}
+
+#[test]
+fn edited_payload_is_modified() {
+    // Test data
+    let mut envelope = seal(1, json!({"version": 1, "money": 300}));
+    envelope["payload"]["money"] = json!(1_000_000);
+
+    // Execute test
+    let (integrity, opened) = open(envelope);
+    assert_eq!(integrity, SaveIntegrity::Modified);
+    assert_eq!(opened["money"], 1_000_000);
+}
+
+#[test]
+fn edited_version_is_modified() {
+    // Test data
+    let mut envelope = seal(1, json!({"version": 1, "money": 300}));
+    envelope["version"] = json!(0);
+
+    // Execute test
+    assert_eq!(open(envelope).0, SaveIntegrity::Modified);
+}
+
+#[test]
+fn unsealed_save_is_trusted() {
+    // Test data
+    let payload = json!({"money": 300});
+
+    // Execute test
+    assert_eq!(open(payload).0, SaveIntegrity::Ok);
+}
+
+#[test]
+fn stripped_save_is_modified() {
+    // Execute test
+    for version in SEALED_VERSION..=crate::save::SAVE_VERSION {
+        let envelope = seal(version, json!({"version": version, "money": 1_000_000}));
+        let payload = envelope["payload"].clone();
+        assert_eq!(open(payload).0, SaveIntegrity::Modified);
+    }
+    assert_eq!(open(json!({"version": 1, "money": 300})).0, SaveIntegrity::Ok);
+}
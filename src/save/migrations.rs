@@ -0,0 +1,214 @@
+//! Forward migrations between save versions.
+//!
+//! `MIGRATIONS[n]` upgrades a version `n` save to version `n + 1`. Saves
+//! written before the `version` field existed are version 0. Fields that are
+//! purely additive can use `#[serde(default)]` instead of a migration.
+use serde_json::{json, Map, Value};
+
+use crate::{save::{SaveError, SAVE_VERSION}, sim::{customer::Segment, menu::Menu}};
+
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+const MIGRATIONS: [Migration; SAVE_VERSION as usize] = [v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4];
+
+/// The version a raw save was written with. Only a save without the field
+/// is version 0, one that isn't a version number is refused.
+pub fn version_of(value: &Value) -> Result<u32, SaveError> {
+    let Some(version) = value.get("version") else {
+        return Ok(0);
+    };
+    version
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .ok_or_else(|| SaveError::InvalidVersion(version.to_string()))
+}
+
+/// Upgrades a raw save in place until it matches [`SAVE_VERSION`].
+pub fn migrate(value: &mut Value) -> Result<(), SaveError> {
+    let mut version = version_of(value)?;
+    if version > SAVE_VERSION {
+        return Err(SaveError::UnsupportedVersion(version));
+    }
+
+    let object = value.as_object_mut().ok_or(SaveError::Migration {
+        from: version,
+        reason: "save is not an object".to_string(),
+    })?;
+
+    while version < SAVE_VERSION {
+        MIGRATIONS[version as usize](object)
+            .map_err(|reason| SaveError::Migration { from: version, reason })?;
+        version += 1;
+        object.insert("version".to_string(), Value::from(version));
+    }
+
+    Ok(())
+}
+
+/// Version 0 is the untagged save. Its fields are the same as version 1.
+fn v0_to_v1(save: &mut Map<String, Value>) -> Result<(), String> {
+    for field in ["stock", "money", "day", "price", "cup", "favorability", "inventory", "recipe"] {
+        if !save.contains_key(field) {
+            return Err(format!("missing field `{}`", field));
+        }
+    }
+    Ok(())
+}
+
+/// Version 2 tracks inventory as dated lots. Old stock is treated as bought
+/// on the day the game was saved.
+fn v1_to_v2(save: &mut Map<String, Value>) -> Result<(), String> {
+    let day = save.get("day").and_then(Value::as_u64).ok_or("missing field `day`")?;
+    let inventory = save
+        .get("inventory")
+        .and_then(Value::as_object)
+        .ok_or("missing field `inventory`")?;
+
+    let mut lots = Vec::new();
+    for ingredient in ["coffee", "milk", "sugar"] {
+        let quantity = inventory.get(ingredient).and_then(Value::as_f64).unwrap_or(0.0);
+        if quantity > 0.0 {
+            lots.push(json!({ "ingredient": ingredient, "quantity": quantity, "bought_day": day }));
+        }
+    }
+
+    save.insert("inventory".to_string(), json!({ "lots": lots }));
+    Ok(())
+}
+
+/// Version 3 replaces the single recipe, price and stock with a menu. The old
+/// recipe takes the place of the first default drink.
+fn v2_to_v3(save: &mut Map<String, Value>) -> Result<(), String> {
+    let recipe = save.remove("recipe").ok_or("missing field `recipe`")?;
+    let price = save.remove("price").ok_or("missing field `price`")?;
+    let stock = save.remove("stock").unwrap_or(Value::from(0));
+
+    let mut menu = serde_json::to_value(Menu::default()).map_err(|err| err.to_string())?;
+    let first = menu
+        .get_mut("items")
+        .and_then(|items| items.get_mut(0))
+        .and_then(Value::as_object_mut)
+        .ok_or("default menu has no drinks")?;
+    first.insert("recipe".to_string(), recipe);
+    first.insert("price".to_string(), price);
+    first.insert("stock".to_string(), stock);
+    save.insert("menu".to_string(), menu);
+    Ok(())
+}
+
+/// Version 4 replaces the single favorability with a reputation per customer
+/// segment. Every segment starts from the old value, in the ledger too.
+fn v3_to_v4(save: &mut Map<String, Value>) -> Result<(), String> {
+    let reputation = |favorability: Option<Value>| {
+        let value = favorability.as_ref().and_then(Value::as_f64).unwrap_or(0.5);
+        let segments: Map<String, Value> = Segment::ALL.iter().map(|segment| (segment.id().to_string(), Value::from(value))).collect();
+        json!({ "segments": segments, "buzz": 0.0 })
+    };
+
+    let favorability = save.remove("favorability").ok_or("missing field `favorability`")?;
+    save.insert("reputation".to_string(), reputation(Some(favorability)));
+
+    let days = save
+        .get_mut("ledger")
+        .and_then(|ledger| ledger.get_mut("days"))
+        .and_then(Value::as_array_mut);
+    for day in days.into_iter().flatten().filter_map(Value::as_object_mut) {
+        for (old, new) in [("favorability_start", "reputation_start"), ("favorability_end", "reputation_end")] {
+            let value = reputation(day.remove(old));
+            day.insert(new.to_string(), value);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn load_v0_fixture() {
+    // Test data
+    let json = include_str!("fixtures/v0.json");
+
+    // Execute test
+    let save = crate::save::GameDataSave::from_json(json).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.state.day, 4);
+    assert_eq!(save.state.money, 412);
+    assert_eq!(save.state.menu.items[0].price, 9);
+    assert_eq!(save.state.cup, 36);
+    assert_eq!(save.state.inventory.total("milk"), 640.0);
+    assert_eq!(save.state.menu.items[0].recipe.get("coffee"), 7.0);
+    assert_eq!(save.state.menu.items.len(), 3);
+}
+
+#[test]
+fn load_v1_fixture() {
+    // Test data
+    let json = include_str!("fixtures/v1.json");
+
+    // Execute test
+    let save = crate::save::GameDataSave::from_json(json).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.state.day, 7);
+    assert_eq!(save.state.money, 980);
+    assert!(Segment::ALL.iter().all(|segment| save.state.reputation.standing(*segment) == 0.75));
+    assert_eq!(save.state.reputation.buzz, 0.0);
+    assert_eq!(save.state.inventory.lots.len(), 3);
+    assert!(save.state.inventory.lots.iter().all(|lot| lot.bought_day == 7));
+    assert_eq!(save.state.inventory.total("sugar"), 700.0);
+}
+
+#[test]
+fn load_sealed_v1_fixture() {
+    // Test data
+    let json = include_str!("fixtures/v1_sealed.json");
+
+    // Execute test
+    let save = crate::save::GameDataSave::from_json(json).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.meta.name, "Balance test");
+    assert_eq!(save.state.day, 7);
+    assert_eq!(save.state.money, 980);
+}
+
+#[test]
+fn reject_broken_v0_save() {
+    // Test data
+    let json = r#"{"money": 10, "day": 2}"#;
+
+    // Execute test
+    let result = crate::save::GameDataSave::from_json(json);
+    assert!(matches!(result, Err(SaveError::Migration { from: 0, .. })));
+}
+
+#[test]
+fn reject_malformed_version() {
+    // Test data
+    let versions = [json!("3"), json!(-1), json!(1.5), Value::Null];
+
+    // Execute test
+    assert_eq!(version_of(&json!({"money": 10})).unwrap(), 0);
+    for version in versions {
+        let mut value = json!({"version": version, "money": 10});
+        assert!(matches!(version_of(&value), Err(SaveError::InvalidVersion(_))));
+        assert!(matches!(migrate(&mut value), Err(SaveError::InvalidVersion(_))));
+    }
+}
+
+#[test]
+fn favorability_becomes_reputation() {
+    // Test data
+    let mut value = json!({
+        "version": 3,
+        "favorability": 0.6,
+        "ledger": { "days": [{ "day": 2, "favorability_start": 0.5, "favorability_end": 0.6 }] },
+    });
+
+    // Execute test
+    migrate(&mut value).unwrap();
+    assert_eq!(value["version"], SAVE_VERSION);
+    assert!(value.get("favorability").is_none());
+    assert_eq!(value["reputation"]["segments"]["student"], 0.6);
+    assert_eq!(value["ledger"]["days"][0]["reputation_start"]["segments"]["senior"], 0.5);
+    assert_eq!(value["ledger"]["days"][0]["reputation_end"]["buzz"], 0.0);
+}
@@ -0,0 +1,176 @@
+//! On-disk save format.
+//!
+//! Every save carries a `version`. Loading parses the raw JSON, runs it
+//! through [`migrations::migrate`] up to [`SAVE_VERSION`] and only then
+//! deserializes it into [`GameDataSave`], so old saves keep working after a
+//! schema change. The result is wrapped in a checksummed envelope, see
+//! [`integrity`].
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{save::{integrity::SaveIntegrity, slot::SlotMeta}, sim::state::GameState};
+
+pub mod backup;
+pub mod integrity;
+pub mod migrations;
+pub mod slot;
+
+/// Bump this and add a migration whenever the shape of [`GameState`] changes.
+pub const SAVE_VERSION: u32 = 4;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameDataSave {
+    pub version: u32,
+    #[serde(default)]
+    pub meta: SlotMeta,
+    #[serde(flatten)]
+    pub state: GameState,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Missing,
+    Io(String),
+    Parse(serde_json::Error),
+    Modified,
+    /// The `version` field is there but isn't a version number.
+    InvalidVersion(String),
+    UnsupportedVersion(u32),
+    Migration { from: u32, reason: String },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Missing => write!(f, "save file not found"),
+            SaveError::Io(err) => write!(f, "could not write save file: {}", err),
+            SaveError::Parse(err) => write!(f, "save file is not valid: {}", err),
+            SaveError::Modified => write!(f, "save file was modified outside the game"),
+            SaveError::InvalidVersion(version) => write!(f, "save version {} is not valid", version),
+            SaveError::UnsupportedVersion(version) => {
+                write!(f, "save version {} is newer than this game ({})", version, SAVE_VERSION)
+            }
+            SaveError::Migration { from, reason } => {
+                write!(f, "could not upgrade save from version {}: {}", from, reason)
+            }
+        }
+    }
+}
+
+impl SaveError {
+    pub fn integrity(&self) -> SaveIntegrity {
+        match self {
+            SaveError::Modified => SaveIntegrity::Modified,
+            _ => SaveIntegrity::Corrupt,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(err: serde_json::Error) -> Self {
+        SaveError::Parse(err)
+    }
+}
+
+impl GameDataSave {
+    pub fn new(state: GameState, meta: SlotMeta) -> Self {
+        Self { version: SAVE_VERSION, meta, state }
+    }
+
+    /// Flags the save as modified if it was read as such. Writing seals it
+    /// again, so the flag is all that is left of the edit.
+    pub fn keep_integrity(&mut self, integrity: SaveIntegrity) {
+        self.meta.modified |= integrity == SaveIntegrity::Modified;
+    }
+
+    pub fn to_json(&self) -> Result<String, SaveError> {
+        let payload = serde_json::to_value(self)?;
+        Ok(serde_json::to_string(&integrity::seal(self.version, payload))?)
+    }
+
+    /// Reads a save, refusing one that was modified outside the game.
+    pub fn from_json(json: &str) -> Result<Self, SaveError> {
+        match Self::read(json)? {
+            (save, SaveIntegrity::Ok) => Ok(save),
+            _ => Err(SaveError::Modified),
+        }
+    }
+
+    /// Reads a save and reports its integrity instead of refusing it.
+    pub fn read(json: &str) -> Result<(Self, SaveIntegrity), SaveError> {
+        let value: Value = serde_json::from_str(json)?;
+        let (integrity, mut payload) = integrity::open(value);
+        migrations::migrate(&mut payload)?;
+        Ok((serde_json::from_value(payload)?, integrity))
+    }
+}
+
+#[test]
+fn save_roundtrip() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.money = 1234;
+
+    // Execute test
+    let json = GameDataSave::new(state, SlotMeta::named("Run 1")).to_json().unwrap();
+    let save = GameDataSave::from_json(&json).unwrap();
+
+    assert_eq!(save.version, SAVE_VERSION);
+    assert_eq!(save.meta.name, "Run 1");
+    assert_eq!(save.state.money, 1234);
+    assert_eq!(save.state.day, 1);
+}
+
+#[test]
+fn modified_save_is_refused() {
+    // Test data
+    let json = include_str!("fixtures/v1_modified.json");
+
+    // Execute test
+    assert!(matches!(GameDataSave::from_json(json), Err(SaveError::Modified)));
+
+    let (save, integrity) = GameDataSave::read(json).unwrap();
+    assert_eq!(integrity, SaveIntegrity::Modified);
+    assert_eq!(save.state.money, 1_000_000);
+}
+
+#[test]
+fn corrupt_save() {
+    // Test data
+    let json = r#"{"version":1,"checksum":"0","payload":{"ver"#;
+
+    // Execute test
+    let result = GameDataSave::read(json);
+    assert_eq!(result.unwrap_err().integrity(), SaveIntegrity::Corrupt);
+}
+
+#[test]
+fn save_from_the_future() {
+    // Test data
+    let json = format!(r#"{{"version": {}, "money": 1}}"#, SAVE_VERSION + 1);
+
+    // Execute test
+    let result = GameDataSave::from_json(&json);
+    assert!(matches!(result, Err(SaveError::UnsupportedVersion(_))));
+}
+
+#[test]
+fn rewritten_modified_save_stays_flagged() {
+    // Test data
+    let json = include_str!("fixtures/v1_modified.json");
+    let (mut save, integrity) = GameDataSave::read(json).unwrap();
+
+    // Execute test
+    save.keep_integrity(integrity);
+    save.meta.name = "Renamed".to_string();
+    let (renamed, integrity) = GameDataSave::read(&save.to_json().unwrap()).unwrap();
+    assert_eq!(integrity, SaveIntegrity::Ok);
+    assert_eq!(renamed.meta.name, "Renamed");
+    assert!(renamed.meta.modified);
+
+    let (mut clean, integrity) = GameDataSave::read(include_str!("fixtures/v1_sealed.json")).unwrap();
+    clean.keep_integrity(integrity);
+    assert!(!clean.meta.modified);
+}
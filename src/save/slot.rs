@@ -0,0 +1,147 @@
+//! Save slots: one save file per slot, each carrying a [`SlotMeta`] so the
+//! slot browser can describe a run without loading it.
+use serde::{Deserialize, Serialize};
+
+use crate::sim::state::GameState;
+
+const SLOT_PREFIX: &str = "slot_";
+const SLOT_EXTENSION: &str = ".json";
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlotMeta {
+    pub name: String,
+    pub day: i32,
+    pub money: i32,
+    pub favorability: f32,
+    /// Unix time of the last save, in seconds.
+    pub last_played: i64,
+    /// Total time spent in this run, in seconds.
+    pub play_time: f64,
+    /// Set once a hand-edited save was loaded, renamed or copied in a debug
+    /// build.
+    pub modified: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotInfo {
+    pub id: u32,
+    pub meta: SlotMeta,
+}
+
+impl SlotMeta {
+    pub fn named(name: &str) -> Self {
+        Self { name: name.to_string(), ..Default::default() }
+    }
+
+    pub fn update_from(&mut self, state: &GameState, now: i64, played_secs: f64) {
+        self.day = state.day;
+        self.money = state.money;
+        self.favorability = state.reputation.overall();
+        self.last_played = now;
+        self.play_time += played_secs.max(0.0);
+    }
+
+    pub fn summary(&self) -> String {
+        let summary = format!(
+            "Day {} | {} | {:.0}% | {}",
+            self.day,
+            self.money,
+            self.favorability * 100.0,
+            format_play_time(self.play_time),
+        );
+        if self.modified {
+            format!("{} | modified", summary)
+        } else {
+            summary
+        }
+    }
+}
+
+pub fn format_play_time(secs: f64) -> String {
+    let minutes = (secs / 60.0).floor() as i64;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+pub fn slot_file_name(id: u32) -> String {
+    format!("{}{}{}", SLOT_PREFIX, id, SLOT_EXTENSION)
+}
+
+pub fn slot_id_from_file_name(file_name: &str) -> Option<u32> {
+    file_name
+        .strip_prefix(SLOT_PREFIX)?
+        .strip_suffix(SLOT_EXTENSION)?
+        .parse()
+        .ok()
+}
+
+/// Name of a finished run's final save, kept out of the slot list.
+pub fn archive_file_name(id: u32, timestamp_msec: u64) -> String {
+    format!("{}{}.{}{}", SLOT_PREFIX, id, timestamp_msec, SLOT_EXTENSION)
+}
+
+/// Lowest slot id starting from 1 that isn't taken yet.
+pub fn next_free_slot(used: &[u32]) -> u32 {
+    (1..).find(|id| !used.contains(id)).unwrap()
+}
+
+pub fn copy_name(name: &str) -> String {
+    format!("{} (copy)", name)
+}
+
+/// Most recently played first.
+pub fn sort_slots(slots: &mut [SlotInfo]) {
+    slots.sort_by(|a, b| b.meta.last_played.cmp(&a.meta.last_played).then(a.id.cmp(&b.id)));
+}
+
+#[test]
+fn slot_file_names() {
+    // Execute test
+    assert_eq!(slot_file_name(3), "slot_3.json");
+    assert_eq!(slot_id_from_file_name("slot_3.json"), Some(3));
+    assert_eq!(slot_id_from_file_name("slot_x.json"), None);
+    assert_eq!(slot_id_from_file_name("savegame.json"), None);
+    assert_eq!(slot_id_from_file_name("slot_3.json.tmp"), None);
+    assert_eq!(slot_id_from_file_name(&archive_file_name(3, 1700000000000)), None);
+}
+
+#[test]
+fn next_free_slot_fills_gaps() {
+    // Execute test
+    assert_eq!(next_free_slot(&[]), 1);
+    assert_eq!(next_free_slot(&[1, 2, 4]), 3);
+    assert_eq!(next_free_slot(&[2]), 1);
+}
+
+#[test]
+fn update_meta_from_state() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.day = 5;
+    state.money = 640;
+    let mut meta = SlotMeta::named("Weekend run");
+    meta.play_time = 3000.0;
+
+    // Execute test
+    meta.update_from(&state, 1_700_000_000, 780.0);
+
+    assert_eq!(meta.day, 5);
+    assert_eq!(meta.money, 640);
+    assert_eq!(meta.last_played, 1_700_000_000);
+    assert_eq!(meta.summary(), "Day 5 | 640 | 50% | 1h 03m");
+
+    meta.modified = true;
+    assert_eq!(meta.summary(), "Day 5 | 640 | 50% | 1h 03m | modified");
+}
+
+#[test]
+fn sort_most_recent_first() {
+    // Test data
+    let slot = |id, last_played| SlotInfo { id, meta: SlotMeta { last_played, ..Default::default() } };
+    let mut slots = vec![slot(1, 10), slot(2, 30), slot(3, 20)];
+
+    // Execute test
+    sort_slots(&mut slots);
+    let ids: Vec<u32> = slots.iter().map(|slot| slot.id).collect();
+    assert_eq!(ids, vec![2, 3, 1]);
+}
@@ -1,13 +1,23 @@
 use godot::{classes::{AnimatedSprite2D, Button, INode2D, Node2D, RichTextLabel}, prelude::*};
 
-use crate::{customer::Customer, enums::customer_feedback::CustomerFeedback, get_node_by_abs_path, singletons::game_data::GameDataSingleton};
+use crate::{customer::Customer, enums::customer_feedback::CustomerFeedback, get_node_by_abs_path, queue_manager::QueueManager, sim::{staff::StaffMember, upgrade::Stat}, singletons::game_data::GameDataSingleton, utils::rng::GodotRng};
 
 struct CustomerOrder {
     customer: Gd<Customer>,
+    /// Cups taken out of stock, fewer than ordered when it ran short.
     amount: i32,
+    ordered: i32,
+    /// Menu index of the drink ordered.
+    drink: usize,
     progress: f32,
 }
 
+/// Someone at the counter and the order they are working on.
+struct Worker {
+    member: StaffMember,
+    order: Option<CustomerOrder>,
+}
+
 #[derive(GodotClass)]
 #[class(base=Node2D)]
 pub struct SellingPhase {
@@ -17,7 +27,9 @@ pub struct SellingPhase {
     is_day_over: bool,
     fast_forward_speed: f64, // Time speed multiplier
     serving_speed: f32,
+    /// Orders waiting for a free worker.
     orders: Vec<CustomerOrder>,
+    workers: Vec<Worker>,
     love_count: i32,
     like_count: i32,
     dislike_count: i32,
@@ -44,6 +56,8 @@ pub struct SellingPhase {
     skip_button: Option<Gd<Button>>,
     #[export]
     fast_forward_button: Option<Gd<Button>>,
+    #[export]
+    queue: Option<Gd<QueueManager>>,
 }
 
 #[godot_api]
@@ -60,14 +74,16 @@ impl INode2D for SellingPhase {
             stock_label: None,
             skip_button: None,
             fast_forward_button: None,
+            queue: None,
             animated_sprite: None,
-            current_time: (8 * 60) as f64, // Start at 8:00 AM
-            fast_forward_speed: 1 as f64, // Normal speed
-            end_time: (17 * 60) as f64, // End at 8:00 PM
+            current_time: 8.0 * 60.0, // Start at 8:00 AM
+            fast_forward_speed: 1.0, // Normal speed
+            end_time: 17.0 * 60.0, // End at 8:00 PM
             is_day_over: false,
-            time_multiplier: 5 as f64, // Default time multiplier
+            time_multiplier: 5.0, // Default time multiplier
             serving_speed: 1.0, // Default serving speed
             orders: Vec::new(),
+            workers: Vec::new(),
             love_count: 0,
             like_count: 0,
             dislike_count: 0,
@@ -77,12 +93,24 @@ impl INode2D for SellingPhase {
     fn ready(&mut self) {
         let game_data = GameDataSingleton::get_instance();
 
-        let text = format!("{}", game_data.bind().money);
+        let text = format!("{}", game_data.bind().state.money);
         self.money_label.as_mut().unwrap().set_text(&text);
 
-        self.get_day_label().unwrap().set_text(&format!("Day {}", game_data.bind().day));
+        let (day, weather) = (game_data.bind().state.day, game_data.bind().state.weather);
+        self.get_day_label().unwrap().set_text(&format!("Day {} - {}", day, weather.name()));
+        self.serving_speed = game_data.bind().state.modifiers().value(Stat::ServingSpeed);
+        self.workers = game_data.bind().state.workers()
+            .into_iter()
+            .map(|member| Worker { member, order: None })
+            .collect();
+
+        // what people in line can expect: a cup takes `time_multiplier` game
+        // minutes at speed 1, and everyone at the counter works in parallel
+        let speed = self.serving_speed * self.workers.iter().map(|worker| worker.member.speed).sum::<f32>();
+        let minutes_per_cup = self.time_multiplier as f32 / speed;
+        self.get_queue().unwrap().bind_mut().set_minutes_per_cup(minutes_per_cup);
 
-        self.get_stock_label().unwrap().set_text(&format!("Stock: {}", game_data.bind().stock));
+        self.update_stock_label();
 
         self.get_skip_button().unwrap()
             .signals()
@@ -101,7 +129,7 @@ impl INode2D for SellingPhase {
 
     fn process(&mut self, _delta: f64) {
         self.progress_time(_delta);
-        self.serve_customer(_delta);
+        self.serve_customers(_delta);
     }  
 }     
 
@@ -131,52 +159,117 @@ impl SellingPhase {
         self.clock_label.as_mut().unwrap().set_text(&text);
     }
 
-    pub fn update_orders(&mut self, mut customer: Gd<Customer>, amount: i32) {
+    pub fn update_orders(&mut self, mut customer: Gd<Customer>, amount: i32, drink: i32) {
         let mut game_data = GameDataSingleton::get_instance();
-        // let mut game_data = game_data.bind_mut();
-        
-        if game_data.bind().stock < amount {
-            customer.bind_mut().complete_order(false);
+        let drink = drink as usize;
+
+        let taken = game_data.bind_mut().state.take_order(drink, amount);
+        if taken == 0 {
+            // the customer is still busy placing the order, send them off
+            // once they are done and have joined the line
+            let mut queue = self.get_queue().unwrap();
+            customer.run_deferred_gd(move |mut customer| {
+                customer.bind_mut().complete_order(None, 0);
+                queue.bind_mut().leave(&customer);
+            });
             return;
         }
-        game_data.bind_mut().stock -= amount;
-        self.orders.push(CustomerOrder { customer, amount, progress: 0.0 });
-        godot_print!("Stock: {} -> {}", game_data.bind().stock + amount, game_data.bind().stock);
-        self.get_stock_label().unwrap().set_text(&format!("Stock: {}", game_data.bind().stock));
-    }
-
-    fn serve_customer(&mut self, delta: f64) {
-        let mut paid_amount = 0;
-        let mut feedback = CustomerFeedback::None;
-        
-        // mutable borrow self
-        {
-            let game_data = GameDataSingleton::get_instance();
-            let order = self.orders.get_mut(0);
-            if order.is_none() {
-                return;
+        self.orders.push(CustomerOrder { customer, amount: taken, ordered: amount, drink, progress: 0.0 });
+        godot_print!("Ordered {}/{} x {}", taken, amount, game_data.bind().state.menu.items[drink].name);
+        self.update_stock_label();
+    }
+
+    fn update_stock_label(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let menu = &game_data.bind().state.menu;
+        let drinks: Vec<String> = menu.items
+            .iter()
+            .filter(|item| item.stock > 0)
+            .map(|item| format!("{} {}", item.name, item.stock))
+            .collect();
+        let text = if drinks.is_empty() {
+            "Stock: 0".to_string()
+        } else {
+            format!("Stock: {}", drinks.join(" | "))
+        };
+        self.get_stock_label().unwrap().set_text(&text);
+    }
+
+    /// Free workers take the next order in line, then everyone works on
+    /// their own order at their own pace.
+    fn serve_customers(&mut self, delta: f64) {
+        self.tick_patience(delta);
+
+        for worker in self.workers.iter_mut().filter(|worker| worker.order.is_none()) {
+            if self.orders.is_empty() {
+                break;
             }
-            let order = order.unwrap();
-            
-            let progress = delta as f32 * self.serving_speed * self.fast_forward_speed as f32;
-            order.progress += progress;
-            if order.progress >= order.amount as f32 { // order complete remove order queue and change customer state
-                godot_print!("Served customer {}", order.customer.bind().get_variant().unwrap().get_name());
-
-                feedback = order.customer.bind_mut().complete_order(true);
-                
-                paid_amount = order.amount * game_data.bind().price;
-                self.orders.remove(0);
+            worker.order = Some(self.orders.remove(0));
+        }
+
+        let progress = delta as f32 * self.serving_speed * self.fast_forward_speed as f32;
+        let mut served = Vec::new();
+        for (index, worker) in self.workers.iter_mut().enumerate() {
+            let Some(order) = worker.order.as_mut() else {
+                continue;
+            };
+            order.progress += progress * worker.member.speed;
+            if order.progress >= order.amount as f32 {
+                served.push((index, worker.order.take().unwrap()));
             }
         }
-        
+
+        for (worker, order) in served {
+            self.complete_order(worker, order);
+        }
+    }
+
+    /// Everyone who ordered waits a little longer. People still in line give
+    /// up once their patience runs out, the ones being served stay but get
+    /// grumpier.
+    fn tick_patience(&mut self, delta: f64) {
+        let minutes = (delta * self.fast_forward_speed * self.time_multiplier) as f32;
+        for order in self.workers.iter_mut().filter_map(|worker| worker.order.as_mut()) {
+            order.customer.bind_mut().wait(minutes);
+        }
+
+        let (abandoned, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.orders)
+            .into_iter()
+            .partition(|order| order.customer.clone().bind_mut().wait(minutes));
+        self.orders = waiting;
+        for order in abandoned {
+            self.abandon_order(order);
+        }
+    }
+
+    fn abandon_order(&mut self, mut order: CustomerOrder) {
+        let segments = order.customer.bind().group_segments();
+        order.customer.bind_mut().abandon();
+        self.get_queue().unwrap().bind_mut().leave(&order.customer);
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().state.abandon_order(&segments, order.drink, order.amount, order.ordered);
+        self.update_stock_label();
+    }
+
+    fn complete_order(&mut self, worker: usize, mut order: CustomerOrder) {
+        let member = self.workers[worker].member.clone();
+        godot_print!("{} served customer {}", member.name, order.customer.bind().get_variant().unwrap().get_name());
+
+        let mut game_data = GameDataSingleton::get_instance();
+        let recipe = game_data.bind().state.menu.items[order.drink].recipe.clone();
+        let made = member.make(&recipe, &mut GodotRng::new());
+        let feedback = order.customer.bind_mut().complete_order(Some(made), order.amount);
+        self.get_queue().unwrap().bind_mut().leave(&order.customer);
+        game_data.bind_mut().state.record_serve(&member.name, order.amount, &feedback);
+
         self.increase_counter(feedback);
-        self.getting_paid(paid_amount);
+        self.getting_paid(order.drink, order.amount);
     }
 
-    fn getting_paid(&mut self, amount: i32) {
+    fn getting_paid(&mut self, drink: usize, sold_amount: i32) {
         let mut game_data = GameDataSingleton::get_instance();
-        let money = game_data.bind_mut().add_money(amount);
+        game_data.bind_mut().state.sell(drink, sold_amount);
+        let money = game_data.bind().state.money;
         let text = format!("{}", money);
         self.money_label.as_mut().unwrap().set_text(&text);
     }
@@ -207,13 +300,16 @@ impl SellingPhase {
 
     fn end_day(&mut self) {
         let mut game_data = GameDataSingleton::get_instance();
-        game_data.bind_mut().day += 1;
-        game_data.bind_mut().stock = 0;
+        game_data.bind_mut().end_day();
 
-        game_data.bind_mut().save_game();
+        if game_data.bind().state.is_bankrupt() {
+            game_data.bind_mut().game_over();
+        } else {
+            game_data.bind_mut().save_game();
+        }
 
         let mut tree = self.base().get_tree().unwrap();
-        tree.change_scene_to_file("res://scenes/prep_phase.tscn");
+        tree.change_scene_to_file("res://scenes/day_report.tscn");
     }
 
     fn toggle_fast_forward(&mut self) {
@@ -0,0 +1,120 @@
+use godot::{classes::{HBoxContainer, IHBoxContainer, LineEdit, RichTextLabel, TextureRect}, prelude::*};
+
+use crate::{sim::{catalog::Product, market::{MarketEvent, Trend, CUP}, state::GameState}, singletons::game_data::GameDataSingleton, supplier_product::SupplierProduct};
+
+#[derive(GodotClass)]
+#[class(base=HBoxContainer)]
+pub struct ShopRow {
+    base: Base<HBoxContainer>,
+    product: Option<Product>,
+
+    // Change or add your own properties here
+    #[export]
+    icon_rect: Option<Gd<TextureRect>>,
+    #[export]
+    name_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    owned_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    price_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    amount_input: Option<Gd<LineEdit>>,
+}
+
+#[godot_api]
+impl IHBoxContainer for ShopRow {
+    fn init(base: Base<HBoxContainer>) -> Self {
+        Self {
+            base,
+            product: None,
+            icon_rect: None,
+            name_label: None,
+            owned_label: None,
+            price_label: None,
+            amount_input: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let amount_input = self.get_amount_input().unwrap();
+        amount_input.signals()
+            .text_changed()
+            .connect_other(&*self, Self::on_amount_input_changed);
+    }
+}
+
+#[godot_api]
+impl ShopRow {
+    #[signal]
+    pub fn amount_changed();
+
+    /// Returns `false` when the resource doesn't describe a valid product.
+    pub fn set_product(&mut self, resource: &Gd<SupplierProduct>) -> bool {
+        let resource = resource.bind();
+        let game_data = GameDataSingleton::get_instance();
+        let Some(product) = resource.to_product(&game_data.bind().state.ingredients) else {
+            return false;
+        };
+
+        if let Some(icon) = resource.get_icon() {
+            self.get_icon_rect().unwrap().set_texture(&icon);
+        }
+        self.get_name_label().unwrap().set_text(&product.name);
+        self.product = Some(product);
+        true
+    }
+
+    pub fn product(&self) -> Option<&Product> {
+        self.product.as_ref()
+    }
+
+    /// Packs entered by the player.
+    pub fn packs(&self) -> i32 {
+        self.get_amount_input().unwrap().get_text().to_int() as i32
+    }
+
+    pub fn reset(&mut self) {
+        self.get_amount_input().unwrap().set_text("0");
+    }
+
+    /// Shows today's price, the trend, market events and what is in stock.
+    pub fn update(&mut self, state: &GameState) {
+        let Some(product) = self.product.clone() else {
+            return;
+        };
+
+        let owned = if product.good == CUP {
+            state.cup as f32
+        } else {
+            state.inventory.total(&product.good)
+        };
+        self.get_owned_label().unwrap().set_text(&format!("Owned: {} {}", owned, product.unit));
+
+        let unlocked = product.is_unlocked(state.day);
+        self.get_amount_input().unwrap().set_editable(unlocked);
+        if !unlocked {
+            self.get_price_label().unwrap().set_text(&format!("Day {}", product.unlock_day));
+            return;
+        }
+
+        let market = &state.market;
+        let trend = match market.trend(&product.good) {
+            Trend::Up => " ▲",
+            Trend::Down => " ▼",
+            Trend::Steady => "",
+        };
+        let event = match (market.event(&product.good), market.packs_available(&product.good)) {
+            (Some(MarketEvent::Shortage), Some(left)) => format!("\nShortage, {} left", left),
+            (Some(MarketEvent::Spike), _) => "\nPrice spike".to_string(),
+            _ => String::new(),
+        };
+        self.get_price_label().unwrap().set_text(&format!("{}{}{}", market.price(&product), trend, event));
+    }
+
+    fn on_amount_input_changed(&mut self, _: GString) {
+        if self.packs() < 0 {
+            self.reset();
+        }
+        self.signals().amount_changed().emit();
+    }
+}
@@ -1,22 +1,21 @@
-use godot::{classes::{Button, Control, IControl, LineEdit, RichTextLabel}, prelude::*};
+use godot::{classes::{Button, Control, IControl, RichTextLabel, VBoxContainer}, prelude::*};
 
-use crate::{error_alert::ErrorAlert, get_node_by_abs_path, singletons::game_data::GameDataSingleton};
+use crate::{error_alert::ErrorAlert, get_node_by_abs_path, shop_row::ShopRow, sim::{catalog::Product, market}, singletons::game_data::GameDataSingleton, supplier_catalog::SupplierCatalog};
 
 #[derive(GodotClass)]
 #[class(base=Control)]
 pub struct ShopTab {
     base: Base<Control>,
     error_alert: Option<Gd<ErrorAlert>>,
+    rows: Vec<Gd<ShopRow>>,
 
     // Change or add your own properties here
     #[export]
-    coffee_input: Option<Gd<LineEdit>>,
+    catalog: Option<Gd<SupplierCatalog>>,
     #[export]
-    milk_input: Option<Gd<LineEdit>>,
+    row_scene: Option<Gd<PackedScene>>,
     #[export]
-    sugar_input: Option<Gd<LineEdit>>,
-    #[export]
-    cup_input: Option<Gd<LineEdit>>,
+    row_container: Option<Gd<VBoxContainer>>,
     #[export]
     money_label: Option<Gd<RichTextLabel>>,
     #[export]
@@ -28,36 +27,18 @@ impl IControl for ShopTab {
     fn init(base: Base<Control>) -> Self {
         Self {
             base,
-            coffee_input: None,
-            milk_input: None,
-            sugar_input: None,
-            cup_input: None,
+            catalog: None,
+            row_scene: None,
+            row_container: None,
             money_label: None,
             buy_button: None,
             error_alert: None,
+            rows: Vec::new(),
         }
     }
 
     fn ready(&mut self) {
-        let coffee_input = self.get_coffee_input().unwrap();
-        coffee_input.signals()
-            .text_changed()
-            .connect_other(&*self, Self::on_change_buy_amount);
-
-        let milk_input = self.get_milk_input().unwrap();
-        milk_input.signals()
-            .text_changed()
-            .connect_other(&*self, Self::on_change_buy_amount);
-
-        let sugar_input = self.get_sugar_input().unwrap();
-        sugar_input.signals()
-            .text_changed()
-            .connect_other(&*self, Self::on_change_buy_amount);
-
-        let cup_input = self.get_cup_input().unwrap();
-        cup_input.signals()
-            .text_changed()
-            .connect_other(&*self, Self::on_change_buy_amount);
+        self.build_rows();
 
         let buy_button = self.get_buy_button().unwrap();
         buy_button.signals()
@@ -65,6 +46,7 @@ impl IControl for ShopTab {
             .connect_other(&*self, Self::on_buy);
 
         self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
+        self.update_rows();
     }
 
     fn process(&mut self, _delta: f64) {
@@ -76,76 +58,81 @@ impl ShopTab {
     #[signal]
     pub fn on_buy_success();
 
-    fn on_change_buy_amount(&mut self, _: GString) {
-        self.sanitize_input();
-        let coffee = self.get_coffee_input().unwrap().get_text().to_int() as i32;
-        let milk = self.get_milk_input().unwrap().get_text().to_int() as i32;
-        let sugar = self.get_sugar_input().unwrap().get_text().to_int() as i32;
-        let cup = self.get_cup_input().unwrap().get_text().to_int() as i32;
-
-        let coffee_subtotal = coffee * 120;
-        let milk_subtotal = milk * 30;
-        let sugar_subtotal = sugar * 20;
-        let cup_subtotal = cup * 50;
+    /// Adds one row per catalog product above the total.
+    fn build_rows(&mut self) {
+        let products = self.get_catalog().unwrap().bind().get_products();
+        let row_scene = self.get_row_scene().unwrap();
+        let mut row_container = self.get_row_container().unwrap();
+
+        for resource in products.iter_shared() {
+            let mut row = row_scene.instantiate_as::<ShopRow>();
+            row_container.add_child(&row);
+            row_container.move_child(&row, self.rows.len() as i32);
+            if !row.bind_mut().set_product(&resource) {
+                row.queue_free();
+                continue;
+            }
+
+            row.signals()
+                .amount_changed()
+                .connect_other(&*self, Self::on_change_buy_amount);
+            self.rows.push(row);
+        }
+    }
 
-        let total = coffee_subtotal + milk_subtotal + sugar_subtotal + cup_subtotal;
-        self.get_money_label().unwrap().set_text(&format!("{}", total));
+    fn on_change_buy_amount(&mut self) {
+        let order = self.order();
+        let game_data = GameDataSingleton::get_instance();
+        let text = match game_data.bind().state.quote_order(&order) {
+            Ok(total) => {
+                let packs = order.iter().map(|(_, packs)| *packs).max().unwrap_or(0);
+                if market::bulk_discount(packs) > 0.0 {
+                    format!("{} (bulk discount)", total)
+                } else {
+                    format!("{}", total)
+                }
+            }
+            Err(_) => "-".to_string(),
+        };
+        self.get_money_label().unwrap().set_text(&text);
     }
 
     fn on_buy(&mut self) {
         let mut game_data = GameDataSingleton::get_instance();
-        let mut money_label = self.get_money_label().unwrap();
-        let total = money_label.get_text().to_int() as i32;
-        if game_data.bind().money < total {
+        let order = self.order();
+        let result = game_data.bind_mut().state.buy_order(&order);
+        if let Err(message) = result {
             let error_alert = self.error_alert.as_mut().unwrap();
-            error_alert.bind_mut().show_alert(GString::from("Not enough money"));
+            error_alert.bind_mut().show_alert(GString::from(&message));
             return;
         }
 
-        // Mutate game data singleton
-        let coffee = self.get_coffee_input().unwrap().get_text().to_float() as f32;
-        let milk = self.get_milk_input().unwrap().get_text().to_float() as f32;
-        let sugar = self.get_sugar_input().unwrap().get_text().to_float() as f32;
-        let cup = self.get_cup_input().unwrap().get_text().to_int() as i32;
-
-        let coffee = coffee * 300.0;
-        let milk = milk * 1000.0;
-        let sugar = sugar * 1000.0;
-        let cup = cup * 50;
-
-        game_data.bind_mut().add_money(-total);
-        game_data.bind_mut().inventory.coffee += coffee;
-        game_data.bind_mut().inventory.milk += milk;
-        game_data.bind_mut().inventory.sugar += sugar;
-        game_data.bind_mut().cup += cup;
-
         // Reset ui
-        self.get_coffee_input().unwrap().set_text("0");
-        self.get_milk_input().unwrap().set_text("0");
-        self.get_sugar_input().unwrap().set_text("0");
-        self.get_cup_input().unwrap().set_text("0");
-        money_label.set_text("0");
+        for row in self.rows.iter_mut() {
+            row.bind_mut().reset();
+        }
+        self.get_money_label().unwrap().set_text("0");
+        self.update_rows();
 
         self.signals().on_buy_success().emit();
     }
 
-    fn sanitize_input(&mut self) {
-        let coffee = self.get_coffee_input().unwrap().get_text().to_int() as i32;
-        let milk = self.get_milk_input().unwrap().get_text().to_int() as i32;
-        let sugar = self.get_sugar_input().unwrap().get_text().to_int() as i32;
-        let cup = self.get_cup_input().unwrap().get_text().to_int() as i32;
+    /// Packs entered for each product.
+    fn order(&self) -> Vec<(Product, i32)> {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let row = row.bind();
+                row.product().map(|product| (product.clone(), row.packs()))
+            })
+            .collect()
+    }
 
-        if coffee < 0 {
-            self.get_coffee_input().unwrap().set_text("0");
-        }
-        if milk < 0 {
-            self.get_milk_input().unwrap().set_text("0");
-        }
-        if sugar < 0 {
-            self.get_sugar_input().unwrap().set_text("0");
-        }
-        if cup < 0 {
-            self.get_cup_input().unwrap().set_text("0");
+    fn update_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+        for row in self.rows.iter_mut() {
+            row.bind_mut().update(state);
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,217 @@
+use crate::sim::customer::Segment;
+
+pub const DAYS_PER_WEEK: i32 = 7;
+pub const WEEKS_PER_SEASON: i32 = 4;
+pub const DAYS_PER_SEASON: i32 = DAYS_PER_WEEK * WEEKS_PER_SEASON;
+pub const DAYS_PER_YEAR: i32 = DAYS_PER_SEASON * Season::ALL.len() as i32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Mon",
+            Weekday::Tuesday => "Tue",
+            Weekday::Wednesday => "Wed",
+            Weekday::Thursday => "Thu",
+            Weekday::Friday => "Fri",
+            Weekday::Saturday => "Sat",
+            Weekday::Sunday => "Sun",
+        }
+    }
+
+    pub fn is_weekend(&self) -> bool {
+        matches!(self, Weekday::Saturday | Weekday::Sunday)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        }
+    }
+}
+
+/// Where a game day falls in the calendar. Day 1 is the first Monday of spring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Date {
+    pub day: i32,
+    pub weekday: Weekday,
+    /// Week of the run, starting at 1.
+    pub week: i32,
+    /// Week within the season, 1 to [`WEEKS_PER_SEASON`].
+    pub week_of_season: i32,
+    /// Day within the season, 1 to [`DAYS_PER_SEASON`].
+    pub day_of_season: i32,
+    pub season: Season,
+}
+
+impl Date {
+    pub fn of(day: i32) -> Self {
+        let index = (day - 1).max(0);
+        let day_of_year = index % DAYS_PER_YEAR;
+        Self {
+            day,
+            weekday: Weekday::ALL[(index % DAYS_PER_WEEK) as usize],
+            week: index / DAYS_PER_WEEK + 1,
+            week_of_season: day_of_year % DAYS_PER_SEASON / DAYS_PER_WEEK + 1,
+            day_of_season: day_of_year % DAYS_PER_SEASON + 1,
+            season: Season::ALL[(day_of_year / DAYS_PER_SEASON) as usize],
+        }
+    }
+
+    /// Like "Sat 13 Spring, week 2".
+    pub fn label(&self) -> String {
+        format!("{} {} {}, week {}", self.weekday.short_name(), self.day_of_season, self.season.name(), self.week)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarEvent {
+    /// The last weekend of every season, offices have just paid salaries.
+    PaydayWeekend,
+    /// The first two weeks of summer, the students are away.
+    SchoolHoliday,
+    /// One Saturday in autumn the whole street comes out.
+    StreetFestival,
+}
+
+impl CalendarEvent {
+    pub const ALL: [CalendarEvent; 3] = [CalendarEvent::PaydayWeekend, CalendarEvent::SchoolHoliday, CalendarEvent::StreetFestival];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CalendarEvent::PaydayWeekend => "Payday weekend",
+            CalendarEvent::SchoolHoliday => "School holidays",
+            CalendarEvent::StreetFestival => "Street festival",
+        }
+    }
+
+    fn happens_on(&self, date: &Date) -> bool {
+        match self {
+            CalendarEvent::PaydayWeekend => date.week_of_season == WEEKS_PER_SEASON && date.weekday.is_weekend(),
+            CalendarEvent::SchoolHoliday => date.season == Season::Summer && date.week_of_season <= 2,
+            CalendarEvent::StreetFestival => date.season == Season::Autumn && date.day_of_season == 20,
+        }
+    }
+
+    fn segment_multiplier(&self, segment: Segment) -> f32 {
+        match (self, segment) {
+            (CalendarEvent::PaydayWeekend, Segment::OfficeWorker) => 3.0,
+            (CalendarEvent::SchoolHoliday, Segment::Student) => 0.3,
+            _ => 1.0,
+        }
+    }
+
+    fn traffic_multiplier(&self) -> f32 {
+        match self {
+            CalendarEvent::StreetFestival => 1.8,
+            _ => 1.0,
+        }
+    }
+}
+
+pub fn events_on(day: i32) -> Vec<CalendarEvent> {
+    let date = Date::of(day);
+    CalendarEvent::ALL.into_iter().filter(|event| event.happens_on(&date)).collect()
+}
+
+/// Events starting within the next `days` days, counting today, with the
+/// day each one starts. An event already running today is listed once.
+pub fn upcoming_events(today: i32, days: i32) -> Vec<(i32, CalendarEvent)> {
+    let mut upcoming = Vec::new();
+    for day in today..today + days {
+        for event in events_on(day) {
+            let is_new = day == today || !events_on(day - 1).contains(&event);
+            if is_new {
+                upcoming.push((day, event));
+            }
+        }
+    }
+    upcoming
+}
+
+/// How many people of `segment` are around on `day` compared to a weekday.
+pub fn segment_multiplier(day: i32, segment: Segment) -> f32 {
+    let date = Date::of(day);
+    let weekday = match (date.weekday.is_weekend(), segment) {
+        (true, Segment::OfficeWorker) => 0.5,
+        (true, Segment::Student) => 1.2,
+        (true, Segment::Senior) => 1.1,
+        (false, _) => 1.0,
+    };
+    events_on(day).iter().fold(weekday, |multiplier, event| multiplier * event.segment_multiplier(segment))
+}
+
+/// Scales how many people walk past the stand on `day`.
+pub fn traffic_multiplier(day: i32) -> f32 {
+    events_on(day).iter().map(|event| event.traffic_multiplier()).product()
+}
+
+#[test]
+fn dates() {
+    // Test data
+    let first = Date::of(1);
+    let payday = Date::of(27);
+    let next_year = Date::of(DAYS_PER_YEAR + 1);
+
+    // Execute test
+    assert_eq!((first.weekday, first.week, first.season, first.day_of_season), (Weekday::Monday, 1, Season::Spring, 1));
+    assert_eq!((payday.weekday, payday.week_of_season), (Weekday::Saturday, 4));
+    assert_eq!(Date::of(DAYS_PER_SEASON + 1).season, Season::Summer);
+    assert_eq!((next_year.season, next_year.weekday, next_year.week), (Season::Spring, Weekday::Monday, 17));
+    assert_eq!(payday.label(), "Sat 27 Spring, week 4");
+}
+
+#[test]
+fn events_shape_the_crowd() {
+    // Test data
+    let festival = 2 * DAYS_PER_SEASON + 20;
+    let holiday = DAYS_PER_SEASON + 3;
+
+    // Execute test
+    assert_eq!(events_on(27), vec![CalendarEvent::PaydayWeekend]);
+    assert_eq!(events_on(festival), vec![CalendarEvent::StreetFestival]);
+    assert_eq!(segment_multiplier(3, Segment::OfficeWorker), 1.0);
+    assert_eq!(segment_multiplier(6, Segment::OfficeWorker), 0.5);
+    assert_eq!(segment_multiplier(27, Segment::OfficeWorker), 1.5);
+    assert!((segment_multiplier(holiday, Segment::Student) - 0.3).abs() < 1e-6);
+    assert_eq!(traffic_multiplier(festival), 1.8);
+    assert_eq!(traffic_multiplier(3), 1.0);
+
+    assert_eq!(upcoming_events(22, 7), vec![(27, CalendarEvent::PaydayWeekend)]);
+    assert_eq!(upcoming_events(28, 7), vec![(28, CalendarEvent::PaydayWeekend), (29, CalendarEvent::SchoolHoliday)]);
+}
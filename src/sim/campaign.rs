@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sim::upgrade::Modifier;
+
+/// Marketing the player can pay for in the prep phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Campaign {
+    pub id: String,
+    pub name: String,
+    pub cost: i32,
+    /// Days it runs, counting the day it was launched.
+    pub days: i32,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Campaign {
+    pub fn new(id: &str, name: &str, cost: i32, days: i32, modifiers: Vec<Modifier>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            cost,
+            days,
+            modifiers,
+        }
+    }
+}
+
+/// Every campaign on offer, in display order. Defined by designers in a
+/// `CampaignCatalog` resource.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CampaignList {
+    pub campaigns: Vec<Campaign>,
+}
+
+impl CampaignList {
+    pub fn get(&self, id: &str) -> Option<&Campaign> {
+        self.campaigns.iter().find(|campaign| campaign.id == id)
+    }
+}
+
+/// A campaign that is still running.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveCampaign {
+    pub id: String,
+    /// Today included.
+    pub days_left: i32,
+}
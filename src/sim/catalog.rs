@@ -0,0 +1,32 @@
+/// Something the supplier sells. Defined by designers in a `SupplierCatalog`
+/// resource, priced daily by the [`Market`](crate::sim::market::Market).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Product {
+    pub name: String,
+    /// Ingredient id, or [`CUP`](crate::sim::market::CUP) for cups.
+    pub good: String,
+    /// Grams, millilitres or cups in one pack.
+    pub quantity: f32,
+    pub unit: String,
+    /// Price of one pack when the market is calm.
+    pub price: i32,
+    /// First day the product shows up in the shop.
+    pub unlock_day: i32,
+}
+
+impl Product {
+    pub fn new(name: &str, good: &str, quantity: f32, unit: &str, price: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            good: good.to_string(),
+            quantity,
+            unit: unit.to_string(),
+            price,
+            unlock_day: 1,
+        }
+    }
+
+    pub fn is_unlocked(&self, day: i32) -> bool {
+        day >= self.unlock_day
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    enums::customer_feedback::CustomerFeedback,
+    sim::{ingredient::IngredientRegistry, menu::Menu, recipe::CoffeeComponent, rng::SimRng, state::GameState, weather::Weather},
+};
+
+/// Price a customer considers fair when their variant doesn't say.
+pub const REFERENCE_PRICE: f32 = 8.0;
+/// How hard a price above the fair one puts customers off, when their
+/// variant doesn't say. At 1 a price 25% over fair costs 25% of buyers.
+pub const DEFAULT_ELASTICITY: f32 = 1.0;
+/// Share of buyers a price within their budget keeps at worst, some always
+/// stop to look.
+pub const MIN_PRICE_FACTOR: f32 = 0.2;
+/// Share of buyers a bargain brings at best.
+pub const MAX_PRICE_FACTOR: f32 = 1.5;
+/// Lowest score a drink needs before a customer bothers ordering it.
+pub const MIN_CHOICE_SCORE: f32 = 0.5;
+/// Minutes a customer waits for their order before giving up, when their
+/// variant doesn't say.
+pub const DEFAULT_PATIENCE: f32 = 45.0;
+/// Share of their patience a customer can wait without it souring the cup.
+pub const PATIENT_SHARE: f32 = 0.5;
+
+/// The kind of people a customer variant stands for. The calendar decides
+/// how many of each are out on a given day.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Segment {
+    #[default]
+    OfficeWorker,
+    Student,
+    Senior,
+}
+
+impl Segment {
+    pub const ALL: [Segment; 3] = [Segment::OfficeWorker, Segment::Student, Segment::Senior];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Segment::OfficeWorker => "office_worker",
+            Segment::Student => "student",
+            Segment::Senior => "senior",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Segment> {
+        Segment::ALL.into_iter().find(|segment| segment.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Segment::OfficeWorker => "Office workers",
+            Segment::Student => "Students",
+            Segment::Senior => "Seniors",
+        }
+    }
+}
+
+/// Why a customer was lost before ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalkAwayReason {
+    SoldOut,
+    OverBudget,
+    NoMatch,
+    LineTooLong,
+    /// Would have stopped at a fair price, so they didn't stop at all.
+    PriceTooHigh,
+}
+
+impl WalkAwayReason {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            WalkAwayReason::SoldOut => "sold out",
+            WalkAwayReason::OverBudget => "over budget",
+            WalkAwayReason::NoMatch => "nothing they like",
+            WalkAwayReason::LineTooLong => "line too long",
+            WalkAwayReason::PriceTooHigh => "put off by the price",
+        }
+    }
+}
+
+/// What a passer-by does when they see the stand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyDecision {
+    Stop,
+    PassBy,
+    /// Would have stopped if the price were fair to them.
+    PutOffByPrice,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DrinkChoice {
+    /// Menu index of the drink ordered.
+    Order(usize),
+    WalkAway(WalkAwayReason),
+}
+
+/// What a customer likes in a cup, copied out of a `CustomerVariant`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomerProfile {
+    /// Preferred amount per ingredient id.
+    pub preferences: CoffeeComponent,
+    /// Most the customer pays for one cup.
+    pub budget: i32,
+    /// Price they consider fair. Below it they are keener, above it they
+    /// drop off as steeply as `elasticity` says, up to `budget`.
+    pub fair_price: f32,
+    pub elasticity: f32,
+    /// Whose reputation the customer goes by.
+    pub segment: Segment,
+    /// Minutes they wait for their order before giving up.
+    pub patience: f32,
+}
+
+impl Default for CustomerProfile {
+    fn default() -> Self {
+        Self::new(CoffeeComponent::default(), 0)
+    }
+}
+
+impl CustomerProfile {
+    pub fn new(preferences: CoffeeComponent, budget: i32) -> Self {
+        Self {
+            preferences,
+            budget,
+            fair_price: REFERENCE_PRICE,
+            elasticity: DEFAULT_ELASTICITY,
+            segment: Segment::default(),
+            patience: DEFAULT_PATIENCE,
+        }
+    }
+
+    /// Their willingness to pay `price`: how much more or less likely they
+    /// are to stop than at their fair price. Nobody stops for a price over
+    /// their budget.
+    pub fn price_factor(&self, price: i32) -> f32 {
+        if price > self.budget {
+            return 0.0;
+        }
+        let markup = (price as f32 - self.fair_price) / self.fair_price;
+        (1.0 - self.elasticity * markup).clamp(MIN_PRICE_FACTOR, MAX_PRICE_FACTOR)
+    }
+
+    pub fn buy_chance(&self, reputation: f32, price: i32, weather: Weather) -> f32 {
+        reputation * self.price_factor(price) * weather.buy_multiplier()
+    }
+
+    /// Whether a passer-by who rolled `roll` stops, and if not whether a
+    /// fair price would have made them. Also returns the buy chance the roll
+    /// was judged against.
+    pub fn decide(&self, reputation: f32, price: i32, weather: Weather, roll: f32) -> (BuyDecision, f32) {
+        let chance = self.buy_chance(reputation, price, weather);
+        let decision = if roll < chance {
+            BuyDecision::Stop
+        } else if roll < reputation * weather.buy_multiplier() {
+            BuyDecision::PutOffByPrice
+        } else {
+            BuyDecision::PassBy
+        };
+        (decision, chance)
+    }
+
+    /// The same customer with tastes shifted by the weather.
+    pub fn in_weather(&self, weather: Weather) -> CustomerProfile {
+        let mut preferences = self.preferences.clone();
+        preferences.merge(&weather.preference_shift());
+        CustomerProfile { preferences, ..self.clone() }
+    }
+
+    /// Rolls whether a passer-by stops, judging the cheapest drink on sale.
+    pub fn should_buy(&self, state: &GameState, rng: &mut impl SimRng) -> bool {
+        let Some(price) = state.menu.lowest_price() else {
+            return false;
+        };
+        self.decide(state.reputation.of(self.segment), price, state.weather, rng.randf()).0 == BuyDecision::Stop
+    }
+
+    /// How close `recipe` is to this customer's taste, from 0 to 1. Averages
+    /// the weighted difference over every known ingredient the customer has a
+    /// preference for or the recipe contains.
+    pub fn score(&self, recipe: &CoffeeComponent, ingredients: &IngredientRegistry) -> f32 {
+        let judged: BTreeSet<&str> = self.preferences
+            .iter()
+            .chain(recipe.iter().filter(|(_, amount)| *amount > 0.0))
+            .map(|(id, _)| id)
+            .filter(|id| ingredients.contains(id))
+            .collect();
+        if judged.is_empty() {
+            return 1.0;
+        }
+
+        let penalty: f32 = judged
+            .iter()
+            .map(|id| (recipe.get(id) - self.preferences.get(id)).abs() * ingredients.score_weight(id))
+            .sum();
+        let score = 1.0 - penalty / judged.len() as f32;
+        score.clamp(0.0, 1.0)
+    }
+
+    pub fn feedback(&self, recipe: &CoffeeComponent, ingredients: &IngredientRegistry) -> CustomerFeedback {
+        feedback_for_score(self.score(recipe, ingredients))
+    }
+
+    /// Chance from 0 to 1 that they skip a line they expect to wait
+    /// `expected_wait` minutes in. Nobody minds a wait within
+    /// [`PATIENT_SHARE`] of their patience, nobody joins one past all of it.
+    pub fn balk_chance(&self, expected_wait: f32) -> f32 {
+        let share = if self.patience > 0.0 { expected_wait / self.patience } else { 1.0 };
+        ((share - PATIENT_SHARE) / (1.0 - PATIENT_SHARE)).clamp(0.0, 1.0)
+    }
+
+    /// The verdict on a cup after waiting `waited` minutes for it. Past
+    /// [`PATIENT_SHARE`] of their patience it drops a step, past all of it two.
+    pub fn after_wait(&self, feedback: CustomerFeedback, waited: f32) -> CustomerFeedback {
+        let share = if self.patience > 0.0 { waited / self.patience } else { 1.0 };
+        let steps = if share >= 1.0 {
+            2
+        } else if share > PATIENT_SHARE {
+            1
+        } else {
+            0
+        };
+        (0..steps).fold(feedback, |feedback, _| match feedback {
+            CustomerFeedback::Love => CustomerFeedback::Like,
+            CustomerFeedback::Like | CustomerFeedback::Dislike => CustomerFeedback::Dislike,
+            CustomerFeedback::None => CustomerFeedback::None,
+        })
+    }
+
+    /// Picks the prepared drink within budget that scores best, the cheaper
+    /// one on a tie. Walks away when nothing affordable is close enough.
+    pub fn choose_drink(&self, menu: &Menu, ingredients: &IngredientRegistry) -> DrinkChoice {
+        let in_stock = menu.in_stock();
+        if in_stock.is_empty() {
+            return DrinkChoice::WalkAway(WalkAwayReason::SoldOut);
+        }
+
+        let best = in_stock
+            .into_iter()
+            .filter(|&index| menu.items[index].price <= self.budget)
+            .map(|index| (index, self.score(&menu.items[index].recipe, ingredients)))
+            .max_by(|(a, a_score), (b, b_score)| {
+                a_score.total_cmp(b_score).then(menu.items[*b].price.cmp(&menu.items[*a].price))
+            });
+
+        match best {
+            None => DrinkChoice::WalkAway(WalkAwayReason::OverBudget),
+            Some((_, score)) if score < MIN_CHOICE_SCORE => DrinkChoice::WalkAway(WalkAwayReason::NoMatch),
+            Some((index, _)) => DrinkChoice::Order(index),
+        }
+    }
+}
+
+pub fn feedback_for_score(score: f32) -> CustomerFeedback {
+    if score > 0.85 {
+        CustomerFeedback::Love
+    } else if score > 0.5 {
+        CustomerFeedback::Like
+    } else {
+        CustomerFeedback::Dislike
+    }
+}
+
+#[test]
+fn feedback() {
+    // Test data
+    let profile = CustomerProfile::new(CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 10);
+    let ingredients = IngredientRegistry::default();
+
+    // Execute test
+    let feedback = |recipe: CoffeeComponent| profile.feedback(&recipe, &ingredients);
+    assert!(matches!(feedback(CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)])), CustomerFeedback::Love));
+    assert!(matches!(feedback(CoffeeComponent::from([("coffee", 10.0), ("milk", 150.0), ("sugar", 15.0)])), CustomerFeedback::Like));
+    assert!(matches!(feedback(CoffeeComponent::from([("coffee", 20.0), ("sugar", 40.0)])), CustomerFeedback::Dislike));
+}
+
+#[test]
+fn waiting_sours_feedback() {
+    // Test data
+    let profile = CustomerProfile { patience: 40.0, ..Default::default() };
+
+    // Execute test
+    assert_eq!(profile.after_wait(CustomerFeedback::Love, 20.0), CustomerFeedback::Love);
+    assert_eq!(profile.after_wait(CustomerFeedback::Love, 30.0), CustomerFeedback::Like);
+    assert_eq!(profile.after_wait(CustomerFeedback::Love, 40.0), CustomerFeedback::Dislike);
+    assert_eq!(profile.after_wait(CustomerFeedback::Like, 30.0), CustomerFeedback::Dislike);
+    assert_eq!(profile.after_wait(CustomerFeedback::None, 60.0), CustomerFeedback::None);
+}
+
+#[test]
+fn balk_chance() {
+    // Test data
+    let profile = CustomerProfile { patience: 40.0, ..Default::default() };
+
+    // Execute test
+    assert_eq!(profile.balk_chance(20.0), 0.0);
+    assert_eq!(profile.balk_chance(30.0), 0.5);
+    assert_eq!(profile.balk_chance(50.0), 1.0);
+}
+
+#[test]
+fn score_is_weighted_by_registry() {
+    use crate::sim::ingredient::Ingredient;
+
+    // Test data
+    let profile = CustomerProfile::new(CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0)]), 10);
+    let mut ingredients = IngredientRegistry::default();
+    ingredients.ingredients.push(Ingredient::new("ice", "Ice", "g", 1.0 / 100.0, 1));
+    let iced = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("ice", 150.0)]);
+
+    // Execute test
+    assert_eq!(profile.score(&CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0)]), &ingredients), 1.0);
+    assert!((profile.score(&iced, &ingredients) - 0.5).abs() < 1e-6);
+    assert_eq!(profile.score(&iced, &IngredientRegistry::default()), 1.0);
+}
+
+#[test]
+fn buy_chance() {
+    use crate::sim::reputation::Reputation;
+
+    // Test data
+    let profile = CustomerProfile { budget: 16, ..Default::default() };
+    let mut state = GameState::new_game();
+    let mut rng = crate::sim::rng::SeededRng::new(1);
+
+    // Execute test
+    assert_eq!(profile.buy_chance(0.5, 8, Weather::Sunny), 0.5);
+    assert!((profile.buy_chance(0.5, 16, Weather::Sunny) - 0.1).abs() < 1e-6);
+    assert_eq!(profile.buy_chance(0.5, 2, Weather::Sunny), 0.75);
+    assert!((profile.buy_chance(0.5, 8, Weather::Stormy) - 0.4).abs() < 1e-6);
+
+    state.reputation = Reputation::uniform(1.0);
+    assert!(!profile.should_buy(&state, &mut rng));
+}
+
+#[test]
+fn price_sensitivity() {
+    // Test data
+    let student = CustomerProfile { budget: 13, fair_price: 8.0, elasticity: 1.5, ..Default::default() };
+    let office_worker = CustomerProfile { budget: 16, fair_price: 10.0, elasticity: 0.4, ..Default::default() };
+
+    // Execute test
+    assert_eq!(student.price_factor(8), 1.0);
+    assert!((student.price_factor(10) - 0.625).abs() < 1e-6);
+    assert_eq!(student.price_factor(12), 0.25);
+    assert!((office_worker.price_factor(12) - 0.92).abs() < 1e-6);
+    assert_eq!(office_worker.price_factor(5), 1.2);
+    assert_eq!(student.price_factor(13), MIN_PRICE_FACTOR);
+    assert_eq!(student.price_factor(14), 0.0);
+    assert_eq!(office_worker.price_factor(17), 0.0);
+
+    assert_eq!(student.decide(1.0, 12, Weather::Sunny, 0.2), (BuyDecision::Stop, 0.25));
+    assert_eq!(student.decide(1.0, 12, Weather::Sunny, 0.5).0, BuyDecision::PutOffByPrice);
+    assert_eq!(student.decide(0.4, 12, Weather::Sunny, 0.5).0, BuyDecision::PassBy);
+    assert_eq!(student.decide(0.4, 6, Weather::Sunny, 0.45).0, BuyDecision::Stop);
+    assert_eq!(student.decide(0.4, 6, Weather::Sunny, 0.6).0, BuyDecision::PassBy);
+    assert_eq!(student.decide(1.0, 14, Weather::Sunny, 0.01).0, BuyDecision::PutOffByPrice);
+}
+
+#[test]
+fn choose_drink() {
+    // Test data
+    let ingredients = IngredientRegistry::default();
+    let mut menu = Menu::default();
+    let sweet_tooth = CustomerProfile::new(CoffeeComponent::from([("coffee", 6.0), ("milk", 170.0), ("sugar", 18.0)]), 12);
+    let purist = CustomerProfile::new(CoffeeComponent::from([("coffee", 10.0), ("milk", 0.0), ("sugar", 0.0)]), 12);
+
+    // Execute test
+    assert_eq!(sweet_tooth.choose_drink(&menu, &ingredients), DrinkChoice::WalkAway(WalkAwayReason::SoldOut));
+
+    for item in menu.items.iter_mut() {
+        item.stock = 5;
+    }
+    assert_eq!(sweet_tooth.choose_drink(&menu, &ingredients), DrinkChoice::Order(2));
+    assert_eq!(purist.choose_drink(&menu, &ingredients), DrinkChoice::Order(1));
+
+    let broke = CustomerProfile { budget: 9, ..sweet_tooth.clone() };
+    assert_eq!(broke.choose_drink(&menu, &ingredients), DrinkChoice::Order(0));
+    let broke = CustomerProfile { budget: 5, ..sweet_tooth };
+    assert_eq!(broke.choose_drink(&menu, &ingredients), DrinkChoice::WalkAway(WalkAwayReason::OverBudget));
+
+    menu.items[1].stock = 0;
+    assert_eq!(purist.choose_drink(&menu, &ingredients), DrinkChoice::WalkAway(WalkAwayReason::NoMatch));
+}
+
+#[test]
+fn hot_days_favor_iced_drinks() {
+    use crate::sim::ingredient::Ingredient;
+
+    // Test data
+    let mut ingredients = IngredientRegistry::default();
+    ingredients.ingredients.push(Ingredient::new("ice", "Ice", "g", 1.0 / 100.0, 1));
+    let profile = CustomerProfile::new(CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 12);
+    let hot = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]);
+    let iced = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 13.0), ("ice", 80.0)]);
+
+    // Execute test
+    assert!(profile.score(&hot, &ingredients) > profile.score(&iced, &ingredients));
+    let heatwave = profile.in_weather(Weather::Hot);
+    assert_eq!(heatwave.score(&iced, &ingredients), 1.0);
+    assert!(heatwave.score(&hot, &ingredients) < profile.score(&hot, &ingredients));
+    assert_eq!(profile.in_weather(Weather::Sunny), profile);
+}
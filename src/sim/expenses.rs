@@ -0,0 +1,50 @@
+use crate::sim::rng::SimRng;
+
+/// Cart rent, charged every day.
+pub const DAILY_RENT: i32 = 40;
+/// Street vending permit, renewed at the end of every week.
+pub const WEEKLY_PERMIT: i32 = 150;
+pub const PERMIT_INTERVAL_DAYS: i32 = 7;
+/// Chance in percent that something on the cart breaks overnight.
+pub const REPAIR_CHANCE: f32 = 8.0;
+pub const REPAIR_COST_RANGE: (i32, i32) = (60, 180);
+/// Days in a row money may stay negative before the business goes bankrupt.
+pub const GRACE_DAYS: i32 = 3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expense {
+    pub name: &'static str,
+    pub amount: i32,
+}
+
+/// Bills due when `day` closes.
+pub fn due_expenses(day: i32, rng: &mut impl SimRng) -> Vec<Expense> {
+    let mut expenses = vec![Expense { name: "cart rent", amount: DAILY_RENT }];
+    if day > 0 && day % PERMIT_INTERVAL_DAYS == 0 {
+        expenses.push(Expense { name: "vending permit", amount: WEEKLY_PERMIT });
+    }
+    if rng.check_chance(REPAIR_CHANCE) {
+        let (min, max) = REPAIR_COST_RANGE;
+        expenses.push(Expense { name: "repair", amount: rng.randi_range(min, max) });
+    }
+    expenses
+}
+
+#[test]
+fn permit_is_weekly() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let mut rng = SeededRng::new(11);
+
+    // Execute test
+    for day in 1..=28 {
+        let expenses = due_expenses(day, &mut rng);
+        assert_eq!(expenses[0], Expense { name: "cart rent", amount: DAILY_RENT });
+        let permit = expenses.iter().any(|expense| expense.name == "vending permit");
+        assert_eq!(permit, day % 7 == 0);
+        for repair in expenses.iter().filter(|expense| expense.name == "repair") {
+            assert!((REPAIR_COST_RANGE.0..=REPAIR_COST_RANGE.1).contains(&repair.amount));
+        }
+    }
+}
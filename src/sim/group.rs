@@ -0,0 +1,87 @@
+use crate::{enums::customer_feedback::CustomerFeedback, sim::{customer::Segment, rng::SimRng}};
+
+/// Chance in percent that a passer-by comes with company.
+pub const GROUP_CHANCE: f32 = 20.0;
+
+/// People who come by together and order one cup each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    Friends,
+    OfficeTeam,
+    Family,
+}
+
+impl GroupKind {
+    /// The company a customer of `segment` keeps.
+    pub fn of(segment: Segment) -> GroupKind {
+        match segment {
+            Segment::Student => GroupKind::Friends,
+            Segment::OfficeWorker => GroupKind::OfficeTeam,
+            Segment::Senior => GroupKind::Family,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GroupKind::Friends => "friends",
+            GroupKind::OfficeTeam => "office team",
+            GroupKind::Family => "family",
+        }
+    }
+
+    /// Smallest and largest group, the one who orders included.
+    pub fn size_range(&self) -> (i32, i32) {
+        match self {
+            GroupKind::Friends => (2, 3),
+            GroupKind::OfficeTeam => (2, 4),
+            GroupKind::Family => (2, 3),
+        }
+    }
+
+    /// Whether the rest of the group can be of any segment. Friends and
+    /// teams come from the same crowd, families don't.
+    pub fn is_mixed(&self) -> bool {
+        matches!(self, GroupKind::Family)
+    }
+
+    /// How many people come along with a customer of this kind, 1 when they
+    /// come alone.
+    pub fn roll_size(&self, rng: &mut impl SimRng) -> i32 {
+        if !rng.check_chance(GROUP_CHANCE) {
+            return 1;
+        }
+        let (min, max) = self.size_range();
+        rng.randi_range(min, max)
+    }
+}
+
+/// The verdict a group shares when `served` of the `ordered` cups came. Going
+/// short drops it a step, the ones who went without are just as unhappy.
+pub fn shared_feedback(feedback: CustomerFeedback, served: i32, ordered: i32) -> CustomerFeedback {
+    if served >= ordered {
+        return feedback;
+    }
+    match feedback {
+        CustomerFeedback::Love => CustomerFeedback::Like,
+        CustomerFeedback::Like | CustomerFeedback::Dislike => CustomerFeedback::Dislike,
+        CustomerFeedback::None => CustomerFeedback::None,
+    }
+}
+
+#[test]
+fn groups() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let mut rng = SeededRng::new(6);
+
+    // Execute test
+    let sizes: Vec<i32> = (0..500).map(|_| GroupKind::OfficeTeam.roll_size(&mut rng)).collect();
+    assert!(sizes.iter().all(|size| (1..=4).contains(size)));
+    let groups = sizes.iter().filter(|size| **size > 1).count();
+    assert!((50..150).contains(&groups));
+
+    assert_eq!(shared_feedback(CustomerFeedback::Love, 3, 3), CustomerFeedback::Love);
+    assert_eq!(shared_feedback(CustomerFeedback::Love, 2, 3), CustomerFeedback::Like);
+    assert_eq!(shared_feedback(CustomerFeedback::Like, 1, 3), CustomerFeedback::Dislike);
+}
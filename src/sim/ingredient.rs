@@ -0,0 +1,75 @@
+/// Something that goes into a drink. Defined by designers in an
+/// `IngredientCatalog` resource, referred to everywhere else by its `id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ingredient {
+    pub id: String,
+    pub name: String,
+    pub unit: String,
+    /// Score lost per unit a recipe is away from a customer's preference.
+    pub score_weight: f32,
+    /// Days a lot stays usable, counting the day it was bought.
+    pub shelf_life: i32,
+}
+
+impl Ingredient {
+    pub fn new(id: &str, name: &str, unit: &str, score_weight: f32, shelf_life: i32) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            unit: unit.to_string(),
+            score_weight,
+            shelf_life,
+        }
+    }
+}
+
+/// Every ingredient the shop knows about, in display order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientRegistry {
+    pub ingredients: Vec<Ingredient>,
+}
+
+impl Default for IngredientRegistry {
+    fn default() -> Self {
+        Self {
+            ingredients: vec![
+                Ingredient::new("coffee", "Coffee", "g", 1.0 / 10.0, 30),
+                Ingredient::new("milk", "Milk", "mL", 1.0 / 150.0, 3),
+                Ingredient::new("sugar", "Sugar", "g", 1.0 / 15.0, 90),
+            ],
+        }
+    }
+}
+
+impl IngredientRegistry {
+    pub fn get(&self, id: &str) -> Option<&Ingredient> {
+        self.ingredients.iter().find(|ingredient| ingredient.id == id)
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.ingredients.iter().map(|ingredient| ingredient.id.as_str())
+    }
+
+    /// Display name, the id itself for unknown ingredients.
+    pub fn name<'a>(&'a self, id: &'a str) -> &'a str {
+        self.get(id).map_or(id, |ingredient| ingredient.name.as_str())
+    }
+
+    pub fn unit(&self, id: &str) -> &str {
+        self.get(id).map_or("", |ingredient| ingredient.unit.as_str())
+    }
+
+    /// `None` for unknown ingredients, they never spoil.
+    pub fn shelf_life(&self, id: &str) -> Option<i32> {
+        self.get(id).map(|ingredient| ingredient.shelf_life)
+    }
+
+    /// Unknown ingredients don't matter to customers.
+    pub fn score_weight(&self, id: &str) -> f32 {
+        self.get(id).map_or(0.0, |ingredient| ingredient.score_weight)
+    }
+}
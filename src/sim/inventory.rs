@@ -0,0 +1,152 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{ingredient::IngredientRegistry, recipe::CoffeeComponent};
+
+/// A batch of one ingredient bought on the same day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lot {
+    /// Ingredient id, see [`IngredientRegistry`].
+    pub ingredient: String,
+    pub quantity: f32,
+    pub bought_day: i32,
+}
+
+impl Lot {
+    /// First day the lot can no longer be used, `None` if it keeps forever.
+    pub fn expires_on(&self, ingredients: &IngredientRegistry) -> Option<i32> {
+        ingredients.shelf_life(&self.ingredient).map(|days| self.bought_day + days)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Inventory {
+    pub lots: Vec<Lot>,
+}
+
+impl Inventory {
+    pub fn with(component: &CoffeeComponent, day: i32) -> Self {
+        let mut inventory = Self::default();
+        for (ingredient, quantity) in component.iter() {
+            inventory.add(ingredient, quantity, day);
+        }
+        inventory
+    }
+
+    pub fn add(&mut self, ingredient: &str, quantity: f32, day: i32) {
+        if quantity <= 0.0 {
+            return;
+        }
+        match self.lots.iter_mut().find(|lot| lot.ingredient == ingredient && lot.bought_day == day) {
+            Some(lot) => lot.quantity += quantity,
+            None => self.lots.push(Lot { ingredient: ingredient.to_string(), quantity, bought_day: day }),
+        }
+    }
+
+    pub fn total(&self, ingredient: &str) -> f32 {
+        self.lots
+            .iter()
+            .filter(|lot| lot.ingredient == ingredient)
+            .map(|lot| lot.quantity)
+            .sum()
+    }
+
+    pub fn totals(&self) -> CoffeeComponent {
+        let mut totals = CoffeeComponent::default();
+        for lot in self.lots.iter() {
+            totals.add(&lot.ingredient, lot.quantity);
+        }
+        totals
+    }
+
+    /// Takes `quantity` out of the oldest lots first and returns how much was available.
+    pub fn consume(&mut self, ingredient: &str, quantity: f32) -> f32 {
+        self.lots.sort_by_key(|lot| lot.bought_day);
+
+        let mut remaining = quantity;
+        for lot in self.lots.iter_mut().filter(|lot| lot.ingredient == ingredient) {
+            if remaining <= 0.0 {
+                break;
+            }
+            let taken = lot.quantity.min(remaining);
+            lot.quantity -= taken;
+            remaining -= taken;
+        }
+        self.lots.retain(|lot| lot.quantity > 0.0);
+
+        quantity - remaining.max(0.0)
+    }
+
+    pub fn consume_recipe(&mut self, used: &CoffeeComponent) {
+        for (ingredient, quantity) in used.iter() {
+            self.consume(ingredient, quantity);
+        }
+    }
+
+    /// Removes every lot that can't be used on `day` anymore.
+    pub fn remove_expired(&mut self, day: i32, ingredients: &IngredientRegistry) -> Vec<Lot> {
+        let (expired, fresh) = self.lots.drain(..).partition(|lot| lot.expires_on(ingredients).is_some_and(|expiry| expiry <= day));
+        self.lots = fresh;
+        expired
+    }
+
+    /// Days until the oldest lot of `ingredient` goes off, if there is any.
+    pub fn days_until_expiry(&self, ingredient: &str, today: i32, ingredients: &IngredientRegistry) -> Option<i32> {
+        self.lots
+            .iter()
+            .filter(|lot| lot.ingredient == ingredient)
+            .filter_map(|lot| lot.expires_on(ingredients))
+            .map(|expiry| expiry - today)
+            .min()
+    }
+}
+
+#[test]
+fn consume_oldest_first() {
+    // Test data
+    let mut inventory = Inventory::default();
+    inventory.add("milk", 1000.0, 2);
+    inventory.add("milk", 500.0, 1);
+    inventory.add("sugar", 100.0, 1);
+
+    // Execute test
+    let consumed = inventory.consume("milk", 700.0);
+
+    assert_eq!(consumed, 700.0);
+    assert_eq!(inventory.total("milk"), 800.0);
+    assert_eq!(inventory.lots.iter().filter(|lot| lot.ingredient == "milk").count(), 1);
+    assert_eq!(inventory.lots.iter().find(|lot| lot.ingredient == "milk").unwrap().bought_day, 2);
+    assert_eq!(inventory.total("sugar"), 100.0);
+}
+
+#[test]
+fn consume_more_than_owned() {
+    // Test data
+    let mut inventory = Inventory::default();
+    inventory.add("coffee", 10.0, 1);
+
+    // Execute test
+    assert_eq!(inventory.consume("coffee", 25.0), 10.0);
+    assert_eq!(inventory.total("coffee"), 0.0);
+    assert!(inventory.lots.is_empty());
+}
+
+#[test]
+fn expired_lots_are_removed() {
+    // Test data
+    let ingredients = IngredientRegistry::default();
+    let mut inventory = Inventory::default();
+    inventory.add("milk", 1000.0, 1);
+    inventory.add("milk", 1000.0, 2);
+    inventory.add("coffee", 300.0, 1);
+
+    // Execute test
+    assert_eq!(inventory.days_until_expiry("milk", 1, &ingredients), Some(3));
+    assert!(inventory.remove_expired(3, &ingredients).is_empty());
+
+    let expired = inventory.remove_expired(4, &ingredients);
+    assert_eq!(expired, vec![Lot { ingredient: "milk".to_string(), quantity: 1000.0, bought_day: 1 }]);
+    assert_eq!(inventory.total("milk"), 1000.0);
+    assert_eq!(inventory.days_until_expiry("milk", 4, &ingredients), Some(1));
+    assert_eq!(inventory.days_until_expiry("sugar", 4, &ingredients), None);
+}
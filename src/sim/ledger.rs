@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::customer_feedback::CustomerFeedback, sim::{customer::{Segment, WalkAwayReason}, reputation::Reputation, staff::StaffShift}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    Purchase,
+    Sale,
+    Waste,
+    /// Rent, permits, bills and other running costs.
+    Expense,
+    /// Money borrowed (positive) or paid back to the lender (negative).
+    Loan,
+    /// Cart upgrades. Kept out of the day's profit like loans are.
+    Upgrade,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    pub kind: TransactionKind,
+    pub item: String,
+    pub quantity: f32,
+    /// Money in (positive) or out (negative).
+    pub amount: i32,
+}
+
+/// Everything that happened on one day, from the prep phase to closing.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayLedger {
+    pub day: i32,
+    pub transactions: Vec<Transaction>,
+    pub love: i32,
+    pub like: i32,
+    pub dislike: i32,
+    pub cups_prepared: i32,
+    pub cups_sold: i32,
+    pub reputation_start: Reputation,
+    pub reputation_end: Reputation,
+    /// Customers' choices by drink name.
+    pub orders: BTreeMap<String, i32>,
+    /// Customers lost before ordering, by reason.
+    pub walk_aways: BTreeMap<WalkAwayReason, i32>,
+    /// Cups ordered by customers who then gave up waiting.
+    pub abandoned: i32,
+    /// Cups ordered that had sold out by the time the order was taken, for a
+    /// group that got only part of theirs or a single customer who got none.
+    /// A party that then gives up counts as `abandoned` instead.
+    pub cups_short: i32,
+    /// Orders served and wages paid, by who was at the counter.
+    pub shifts: BTreeMap<String, StaffShift>,
+}
+
+impl DayLedger {
+    pub fn new(day: i32) -> Self {
+        Self { day, ..Default::default() }
+    }
+
+    fn total(&self, kind: TransactionKind) -> i32 {
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.kind == kind)
+            .map(|transaction| transaction.amount)
+            .sum()
+    }
+
+    pub fn revenue(&self) -> i32 {
+        self.total(TransactionKind::Sale)
+    }
+
+    /// Money spent on supplies.
+    pub fn cost_of_goods(&self) -> i32 {
+        -self.total(TransactionKind::Purchase)
+    }
+
+    /// Running costs like rent and repairs.
+    pub fn expenses(&self) -> i32 {
+        -self.total(TransactionKind::Expense)
+    }
+
+    /// Money put into cart upgrades.
+    pub fn investments(&self) -> i32 {
+        -self.total(TransactionKind::Upgrade)
+    }
+
+    pub fn profit(&self) -> i32 {
+        self.revenue() - self.cost_of_goods() - self.expenses()
+    }
+
+    pub fn wasted(&self, item: &str) -> f32 {
+        self.transactions
+            .iter()
+            .filter(|transaction| transaction.kind == TransactionKind::Waste && transaction.item == item)
+            .map(|transaction| transaction.quantity)
+            .sum()
+    }
+
+    /// Change of the overall reputation, buzz included.
+    pub fn reputation_change(&self) -> f32 {
+        self.reputation_end.overall() - self.reputation_start.overall()
+    }
+
+    /// Change of one segment's lasting standing.
+    pub fn segment_change(&self, segment: Segment) -> f32 {
+        self.reputation_end.standing(segment) - self.reputation_start.standing(segment)
+    }
+
+    pub fn record(&mut self, kind: TransactionKind, item: &str, quantity: f32, amount: i32) {
+        self.transactions.push(Transaction { kind, item: item.to_string(), quantity, amount });
+    }
+
+    pub fn record_order(&mut self, drink: &str) {
+        *self.orders.entry(drink.to_string()).or_default() += 1;
+    }
+
+    pub fn record_walk_away(&mut self, reason: WalkAwayReason) {
+        *self.walk_aways.entry(reason).or_default() += 1;
+    }
+
+    pub fn shift_mut(&mut self, name: &str) -> &mut StaffShift {
+        self.shifts.entry(name.to_string()).or_default()
+    }
+
+    pub fn total_walk_aways(&self) -> i32 {
+        self.walk_aways.values().sum()
+    }
+
+    /// Cups that could have been sold, by why they weren't. Every customer
+    /// lost counts as one cup.
+    pub fn lost_sales(&self) -> Vec<(&'static str, i32)> {
+        self.walk_aways
+            .iter()
+            .map(|(reason, count)| (reason.describe(), *count))
+            .chain([("gave up waiting", self.abandoned), ("not enough cups", self.cups_short)])
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    pub fn total_lost_sales(&self) -> i32 {
+        self.lost_sales().iter().map(|(_, count)| count).sum()
+    }
+
+    pub fn record_feedback(&mut self, feedback: &CustomerFeedback) {
+        match feedback {
+            CustomerFeedback::Love => self.love += 1,
+            CustomerFeedback::Like => self.like += 1,
+            CustomerFeedback::Dislike => self.dislike += 1,
+            CustomerFeedback::None => (),
+        }
+    }
+}
+
+/// Day by day history of the run.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ledger {
+    pub days: Vec<DayLedger>,
+}
+
+impl Ledger {
+    pub fn day(&self, day: i32) -> Option<&DayLedger> {
+        self.days.iter().find(|ledger| ledger.day == day)
+    }
+
+    /// The ledger for `day`, opened on first use.
+    pub fn day_mut(&mut self, day: i32) -> &mut DayLedger {
+        let index = match self.days.iter().position(|ledger| ledger.day == day) {
+            Some(index) => index,
+            None => {
+                self.days.push(DayLedger::new(day));
+                self.days.len() - 1
+            }
+        };
+        &mut self.days[index]
+    }
+
+    /// Everything `name` did since `from_day`.
+    pub fn shift_totals(&self, name: &str, from_day: i32) -> StaffShift {
+        let mut totals = StaffShift::default();
+        for shift in self.days.iter().filter(|ledger| ledger.day >= from_day).filter_map(|ledger| ledger.shifts.get(name)) {
+            totals.merge(shift);
+        }
+        totals
+    }
+}
+
+#[test]
+fn day_totals() {
+    // Test data
+    let mut ledger = DayLedger::new(3);
+    ledger.record(TransactionKind::Purchase, "coffee", 300.0, -120);
+    ledger.record(TransactionKind::Purchase, "cup", 50.0, -50);
+    ledger.record(TransactionKind::Sale, "coffee cup", 2.0, 20);
+    ledger.record(TransactionKind::Sale, "coffee cup", 1.0, 10);
+    ledger.record(TransactionKind::Waste, "coffee cup", 3.0, 0);
+    ledger.record(TransactionKind::Expense, "cart rent", 1.0, -40);
+    ledger.reputation_start = Reputation::uniform(0.5);
+    ledger.reputation_end = Reputation::uniform(0.57);
+
+    // Execute test
+    assert_eq!(ledger.revenue(), 30);
+    assert_eq!(ledger.cost_of_goods(), 170);
+    assert_eq!(ledger.expenses(), 40);
+    assert_eq!(ledger.profit(), -180);
+    assert_eq!(ledger.wasted("coffee cup"), 3.0);
+    assert!((ledger.reputation_change() - 0.07).abs() < 1e-6);
+    assert!((ledger.segment_change(Segment::Student) - 0.07).abs() < 1e-6);
+}
+
+#[test]
+fn lost_sales_by_reason() {
+    // Test data
+    let mut ledger = DayLedger::new(1);
+    ledger.record_walk_away(WalkAwayReason::SoldOut);
+    ledger.record_walk_away(WalkAwayReason::PriceTooHigh);
+    ledger.record_walk_away(WalkAwayReason::PriceTooHigh);
+    ledger.abandoned = 3;
+
+    // Execute test
+    assert_eq!(ledger.lost_sales(), vec![("sold out", 1), ("put off by the price", 2), ("gave up waiting", 3)]);
+    assert_eq!(ledger.total_lost_sales(), 6);
+    assert_eq!(DayLedger::new(2).total_lost_sales(), 0);
+}
+
+#[test]
+fn day_mut_opens_each_day_once() {
+    // Test data
+    let mut ledger = Ledger::default();
+
+    // Execute test
+    ledger.day_mut(1).record_feedback(&CustomerFeedback::Love);
+    ledger.day_mut(1).record_feedback(&CustomerFeedback::Dislike);
+    ledger.day_mut(2).record_feedback(&CustomerFeedback::Like);
+
+    assert_eq!(ledger.days.len(), 2);
+    assert_eq!(ledger.day(1).unwrap().love, 1);
+    assert_eq!(ledger.day(1).unwrap().dislike, 1);
+    assert_eq!(ledger.day(2).unwrap().like, 1);
+    assert!(ledger.day(3).is_none());
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// Credit every run starts with.
+const BASE_CREDIT: i32 = 200;
+/// Extra credit at full reputation.
+const REPUTATION_CREDIT: i32 = 600;
+/// Extra credit for every day the business has been running.
+const CREDIT_PER_DAY: i32 = 20;
+const MAX_CREDIT: i32 = 2000;
+
+pub const LOAN_TERM_DAYS: i32 = 7;
+pub const DAILY_INTEREST: f32 = 0.02;
+/// Added to the balance when an installment can't be paid.
+pub const LATE_FEE: i32 = 15;
+/// Reputation lost with every segment for every missed installment.
+pub const MISSED_PAYMENT_PENALTY: f32 = 0.05;
+
+/// Money borrowed from the lender, repaid in daily installments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loan {
+    pub principal: i32,
+    /// What is still owed, interest and fees included.
+    pub balance: i32,
+    pub daily_rate: f32,
+    /// Installments left in the schedule. Once it runs out the whole balance is due.
+    pub days_left: i32,
+    pub missed_payments: i32,
+}
+
+impl Loan {
+    pub fn new(amount: i32) -> Self {
+        Self {
+            principal: amount,
+            balance: amount,
+            daily_rate: DAILY_INTEREST,
+            days_left: LOAN_TERM_DAYS,
+            missed_payments: 0,
+        }
+    }
+
+    /// Amount due at the end of today.
+    pub fn installment(&self) -> i32 {
+        let days = self.days_left.max(1);
+        (self.balance + days - 1) / days
+    }
+
+    pub fn accrue_interest(&mut self) -> i32 {
+        let interest = (self.balance as f32 * self.daily_rate).round() as i32;
+        self.balance += interest;
+        interest
+    }
+
+    pub fn is_paid_off(&self) -> bool {
+        self.balance <= 0
+    }
+}
+
+/// How much the lender trusts the business in total.
+pub fn credit_limit(reputation: f32, day: i32) -> i32 {
+    let limit = BASE_CREDIT + (reputation.clamp(0.0, 1.0) * REPUTATION_CREDIT as f32) as i32 + day.max(0) * CREDIT_PER_DAY;
+    limit.min(MAX_CREDIT)
+}
+
+#[test]
+fn installments_cover_the_balance() {
+    // Test data
+    let mut loan = Loan::new(500);
+
+    // Execute test
+    let mut paid = 0;
+    while !loan.is_paid_off() {
+        loan.accrue_interest();
+        let installment = loan.installment();
+        loan.balance -= installment;
+        loan.days_left -= 1;
+        paid += installment;
+    }
+    assert_eq!(loan.days_left, 0);
+    assert!(paid > 500);
+}
+
+#[test]
+fn credit_grows_with_favorability_and_days() {
+    // Execute test
+    assert_eq!(credit_limit(0.5, 1), 520);
+    assert!(credit_limit(1.0, 1) > credit_limit(0.2, 1));
+    assert!(credit_limit(0.5, 30) > credit_limit(0.5, 1));
+    assert_eq!(credit_limit(1.0, 500), MAX_CREDIT);
+}
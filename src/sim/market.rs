@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{catalog::Product, rng::SimRng};
+
+/// Largest daily move of a price index before mean reversion.
+const VOLATILITY: f32 = 0.08;
+/// Share of the distance to the base price recovered every day.
+const REVERSION: f32 = 0.15;
+const MIN_INDEX: f32 = 0.6;
+const MAX_INDEX: f32 = 1.8;
+
+const SPIKE_CHANCE: f32 = 5.0;
+const SPIKE_MULTIPLIER: f32 = 1.5;
+const SHORTAGE_CHANCE: f32 = 3.0;
+const SHORTAGE_MULTIPLIER: f32 = 1.25;
+const SHORTAGE_DAYS: i32 = 3;
+/// Relative change below which a price counts as steady.
+const TREND_THRESHOLD: f32 = 0.01;
+/// Packs the supplier sells per day during a shortage.
+const SHORTAGE_PACK_LIMIT: i32 = 2;
+
+/// Minimum packs in one order and the discount they earn, largest tier first.
+pub const BULK_DISCOUNTS: [(i32, f32); 3] = [(20, 0.15), (10, 0.10), (5, 0.05)];
+
+/// The good that restocks cups. Every other good is an ingredient id.
+pub const CUP: &str = "cup";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketEvent {
+    /// One day of steep prices.
+    Spike,
+    /// Several days of higher prices and a daily pack limit.
+    Shortage,
+}
+
+impl MarketEvent {
+    pub fn multiplier(&self) -> f32 {
+        match self {
+            MarketEvent::Spike => SPIKE_MULTIPLIER,
+            MarketEvent::Shortage => SHORTAGE_MULTIPLIER,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Up,
+    Down,
+    Steady,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GoodPrice {
+    /// Random walk around 1.0, the catalog price.
+    pub index: f32,
+    /// Yesterday's [`Market::multiplier`].
+    pub yesterday: f32,
+    pub event: Option<MarketEvent>,
+    pub event_days: i32,
+    pub bought_today: i32,
+}
+
+impl Default for GoodPrice {
+    fn default() -> Self {
+        Self { index: 1.0, yesterday: 1.0, event: None, event_days: 0, bought_today: 0 }
+    }
+}
+
+/// Supplier prices for today as multipliers of the catalog prices, keyed by
+/// ingredient id or [`CUP`]. Goods that never moved sell at the catalog price.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Market {
+    pub prices: BTreeMap<String, GoodPrice>,
+}
+
+impl Market {
+    fn entry(&self, good: &str) -> GoodPrice {
+        self.prices.get(good).cloned().unwrap_or_default()
+    }
+
+    /// Today's price relative to the catalog price.
+    pub fn multiplier(&self, good: &str) -> f32 {
+        let entry = self.entry(good);
+        entry.index * entry.event.map_or(1.0, |event| event.multiplier())
+    }
+
+    /// Today's price of one pack of `product`.
+    pub fn price(&self, product: &Product) -> i32 {
+        ((product.price as f32 * self.multiplier(&product.good)).round() as i32).max(1)
+    }
+
+    pub fn trend(&self, good: &str) -> Trend {
+        let change = self.multiplier(good) / self.entry(good).yesterday;
+        if change > 1.0 + TREND_THRESHOLD {
+            Trend::Up
+        } else if change < 1.0 - TREND_THRESHOLD {
+            Trend::Down
+        } else {
+            Trend::Steady
+        }
+    }
+
+    pub fn event(&self, good: &str) -> Option<MarketEvent> {
+        self.entry(good).event
+    }
+
+    /// Packs left today, `None` when the supplier has no limit.
+    pub fn packs_available(&self, good: &str) -> Option<i32> {
+        let entry = self.entry(good);
+        match entry.event {
+            Some(MarketEvent::Shortage) => Some((SHORTAGE_PACK_LIMIT - entry.bought_today).max(0)),
+            _ => None,
+        }
+    }
+
+    /// Cost of `packs` packs bought in one order, after the bulk discount.
+    pub fn quote(&self, product: &Product, packs: i32) -> i32 {
+        let full_price = (self.price(product) * packs) as f32;
+        (full_price * (1.0 - bulk_discount(packs))).round() as i32
+    }
+
+    pub fn buy(&mut self, good: &str, packs: i32) {
+        self.prices.entry(good.to_string()).or_default().bought_today += packs;
+    }
+
+    /// Moves the price of every good one day forward.
+    pub fn advance<'a>(&mut self, goods: impl IntoIterator<Item = &'a str>, rng: &mut impl SimRng) {
+        for good in goods {
+            let yesterday = self.multiplier(good);
+            let entry = self.prices.entry(good.to_string()).or_default();
+            entry.yesterday = yesterday;
+            entry.bought_today = 0;
+
+            if entry.event_days > 0 {
+                entry.event_days -= 1;
+            }
+            if entry.event_days == 0 {
+                entry.event = None;
+            }
+
+            let step = rng.randf_range(-VOLATILITY, VOLATILITY) + (1.0 - entry.index) * REVERSION;
+            entry.index = (entry.index + step).clamp(MIN_INDEX, MAX_INDEX);
+
+            if entry.event.is_none() {
+                if rng.check_chance(SHORTAGE_CHANCE) {
+                    entry.event = Some(MarketEvent::Shortage);
+                    entry.event_days = SHORTAGE_DAYS;
+                } else if rng.check_chance(SPIKE_CHANCE) {
+                    entry.event = Some(MarketEvent::Spike);
+                    entry.event_days = 1;
+                }
+            }
+        }
+    }
+}
+
+pub fn bulk_discount(packs: i32) -> f32 {
+    BULK_DISCOUNTS
+        .iter()
+        .find(|(min_packs, _)| packs >= *min_packs)
+        .map_or(0.0, |(_, discount)| *discount)
+}
+
+#[test]
+fn calm_market_uses_catalog_prices() {
+    // Test data
+    let market = Market::default();
+    let cups = Product::new("Paper Cups", CUP, 50.0, "cups", 50);
+
+    // Execute test
+    assert_eq!(market.price(&cups), 50);
+    assert_eq!(market.trend("milk"), Trend::Steady);
+    assert_eq!(market.packs_available("sugar"), None);
+    assert_eq!(market.quote(&cups, 4), 200);
+    assert_eq!(market.quote(&cups, 10), 450);
+}
+
+#[test]
+fn prices_walk_within_bounds() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let mut market = Market::default();
+    let mut other = Market::default();
+    let mut rng = SeededRng::new(3);
+    let mut other_rng = SeededRng::new(3);
+    let goods = ["coffee", "milk", "sugar", CUP];
+
+    // Execute test
+    for _ in 0..365 {
+        market.advance(goods, &mut rng);
+        other.advance(goods, &mut other_rng);
+        for good in goods {
+            let index = market.prices[good].index;
+            assert!((MIN_INDEX..=MAX_INDEX).contains(&index));
+        }
+    }
+    assert_eq!(market, other);
+}
+
+#[test]
+fn shortage_limits_packs() {
+    // Test data
+    let mut market = Market::default();
+    market.prices.insert("milk".to_string(), GoodPrice {
+        event: Some(MarketEvent::Shortage),
+        event_days: SHORTAGE_DAYS,
+        ..Default::default()
+    });
+    let milk = Product::new("Fresh Milk 1 L", "milk", 1000.0, "mL", 30);
+
+    // Execute test
+    assert_eq!(market.price(&milk), 38);
+    assert_eq!(market.trend("milk"), Trend::Up);
+    market.buy("milk", 1);
+    assert_eq!(market.packs_available("milk"), Some(1));
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sim::recipe::CoffeeComponent;
+
+/// A drink on the board with its own recipe, price and prepared cups.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub name: String,
+    pub recipe: CoffeeComponent,
+    pub price: i32,
+    /// Cups prepared for today.
+    pub stock: i32,
+}
+
+impl MenuItem {
+    pub fn new(name: &str, recipe: CoffeeComponent, price: i32) -> Self {
+        Self { name: name.to_string(), recipe, price, stock: 0 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Menu {
+    pub items: Vec<MenuItem>,
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self {
+            items: vec![
+                MenuItem::new("Kopi Susu", CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8),
+                MenuItem::new("Black Coffee", CoffeeComponent::from([("coffee", 9.0)]), 6),
+                MenuItem::new("Sweet Latte", CoffeeComponent::from([("coffee", 6.0), ("milk", 180.0), ("sugar", 20.0)]), 10),
+            ],
+        }
+    }
+}
+
+impl Menu {
+    pub fn item(&self, index: usize) -> Option<&MenuItem> {
+        self.items.get(index)
+    }
+
+    pub fn total_stock(&self) -> i32 {
+        self.items.iter().map(|item| item.stock).sum()
+    }
+
+    /// Indices of the drinks with cups left.
+    pub fn in_stock(&self) -> Vec<usize> {
+        (0..self.items.len()).filter(|&index| self.items[index].stock > 0).collect()
+    }
+
+    /// Ingredients needed for every prepared cup, except those of `skip`.
+    pub fn usage_without(&self, skip: Option<usize>) -> CoffeeComponent {
+        let mut usage = CoffeeComponent::default();
+        for (index, item) in self.items.iter().enumerate() {
+            if Some(index) == skip {
+                continue;
+            }
+            usage.merge(&item.recipe.scaled(item.stock as f32));
+        }
+        usage
+    }
+
+    /// Cheapest drink a passer-by can buy right now, `None` when nothing is prepared.
+    pub fn lowest_price(&self) -> Option<i32> {
+        self.items.iter().filter(|item| item.stock > 0).map(|item| item.price).min()
+    }
+
+    pub fn clear_stock(&mut self) {
+        for item in self.items.iter_mut() {
+            item.stock = 0;
+        }
+    }
+}
+
+#[test]
+fn usage_of_prepared_cups() {
+    // Test data
+    let mut menu = Menu::default();
+    menu.items[0].stock = 2;
+    menu.items[1].stock = 3;
+
+    // Execute test
+    assert_eq!(menu.total_stock(), 5);
+    assert_eq!(menu.in_stock(), vec![0, 1]);
+    assert_eq!(menu.lowest_price(), Some(6));
+    assert_eq!(menu.usage_without(None), CoffeeComponent::from([("coffee", 41.0), ("milk", 240.0), ("sugar", 20.0)]));
+    assert_eq!(menu.usage_without(Some(1)), CoffeeComponent::from([("coffee", 14.0), ("milk", 240.0), ("sugar", 20.0)]));
+}
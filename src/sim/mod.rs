@@ -0,0 +1,25 @@
+//! Engine-independent simulation core.
+//!
+//! Everything in here is plain Rust so the business rules can be unit-tested
+//! with `cargo test`. The Godot classes own these types and only translate
+//! between them and nodes.
+pub mod calendar;
+pub mod campaign;
+pub mod catalog;
+pub mod customer;
+pub mod expenses;
+pub mod group;
+pub mod ingredient;
+pub mod inventory;
+pub mod ledger;
+pub mod loan;
+pub mod market;
+pub mod menu;
+pub mod recipe;
+pub mod regulars;
+pub mod reputation;
+pub mod rng;
+pub mod staff;
+pub mod state;
+pub mod upgrade;
+pub mod weather;
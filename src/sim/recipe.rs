@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Amounts keyed by ingredient id, see
+/// [`IngredientRegistry`](crate::sim::ingredient::IngredientRegistry). A
+/// missing ingredient counts as zero.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CoffeeComponent {
+    amounts: BTreeMap<String, f32>,
+}
+
+impl<const N: usize> From<[(&str, f32); N]> for CoffeeComponent {
+    fn from(amounts: [(&str, f32); N]) -> Self {
+        amounts.into_iter().collect()
+    }
+}
+
+impl<'a> FromIterator<(&'a str, f32)> for CoffeeComponent {
+    fn from_iter<T: IntoIterator<Item = (&'a str, f32)>>(iter: T) -> Self {
+        let mut component = Self::default();
+        for (id, amount) in iter {
+            component.set(id, amount);
+        }
+        component
+    }
+}
+
+impl PartialEq for CoffeeComponent {
+    fn eq(&self, other: &Self) -> bool {
+        self.amounts.keys().chain(other.amounts.keys()).all(|id| self.get(id) == other.get(id))
+    }
+}
+
+impl CoffeeComponent {
+    pub fn get(&self, id: &str) -> f32 {
+        self.amounts.get(id).copied().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, id: &str, amount: f32) {
+        self.amounts.insert(id.to_string(), amount);
+    }
+
+    pub fn add(&mut self, id: &str, amount: f32) {
+        *self.amounts.entry(id.to_string()).or_default() += amount;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.amounts.iter().map(|(id, amount)| (id.as_str(), *amount))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amounts.values().all(|amount| *amount == 0.0)
+    }
+
+    /// How many whole cups of `recipe` this component can make.
+    pub fn servings_of(&self, recipe: &CoffeeComponent) -> i32 {
+        let possible_cups = recipe
+            .iter()
+            .filter(|(_, needed)| *needed > 0.0)
+            .map(|(id, needed)| self.get(id) / needed)
+            .fold(f32::INFINITY, f32::min);
+
+        if possible_cups.is_finite() {
+            possible_cups.floor().max(0.0) as i32
+        } else {
+            0
+        }
+    }
+
+    pub fn scaled(&self, factor: f32) -> Self {
+        self.iter().map(|(id, amount)| (id, amount * factor)).collect()
+    }
+
+    pub fn merge(&mut self, other: &CoffeeComponent) {
+        for (id, amount) in other.iter() {
+            self.add(id, amount);
+        }
+    }
+
+    pub fn subtract(&mut self, other: &CoffeeComponent) {
+        for (id, amount) in other.iter() {
+            self.add(id, -amount);
+        }
+    }
+}
+
+#[test]
+fn servings_of() {
+    // Test data
+    let inventory = CoffeeComponent::from([("coffee", 300.0), ("milk", 1000.0), ("sugar", 1000.0)]);
+    let recipe = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]);
+    let black = CoffeeComponent::from([("coffee", 7.0), ("milk", 0.0)]);
+    let iced = CoffeeComponent::from([("coffee", 7.0), ("ice", 50.0)]);
+
+    // Execute test
+    assert_eq!(inventory.servings_of(&recipe), 8);
+    assert_eq!(inventory.servings_of(&black), 42);
+    assert_eq!(inventory.servings_of(&iced), 0);
+    assert_eq!(inventory.servings_of(&CoffeeComponent::default()), 0);
+    assert_eq!(black, CoffeeComponent::from([("coffee", 7.0)]));
+}
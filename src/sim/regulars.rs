@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::customer_feedback::CustomerFeedback, sim::{customer::Segment, reputation::Trend, rng::SimRng}};
+
+/// Most regulars who still come by.
+pub const MAX_REGULARS: usize = 12;
+/// Chance in percent that a customer who loved their cup becomes a regular.
+pub const JOIN_CHANCE: f32 = 35.0;
+/// Chance in percent that a passer-by is a regular, when one is due.
+pub const VISIT_CHANCE: f32 = 25.0;
+/// Disliked cups in a row after which a regular stops coming.
+pub const DISLIKE_STREAK_LIMIT: i32 = 3;
+/// Visits compared by the satisfaction trend.
+const TREND_WINDOW: usize = 3;
+/// Visits kept per regular, older ones are forgotten. Loyalty is earned
+/// within this many visits.
+pub const MAX_VISITS: usize = 30;
+const NAMES: [&str; 16] = [
+    "Adi", "Bayu", "Cahya", "Dian", "Endah", "Fitri", "Galih", "Hana",
+    "Indra", "Jaya", "Kartika", "Lukas", "Maya", "Nanda", "Oki", "Putri",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Loyalty {
+    Newcomer,
+    Regular,
+    Loyal,
+}
+
+impl Loyalty {
+    /// Loyalty earned by a number of visits that ended with a cup they liked.
+    pub fn of(happy_visits: usize) -> Loyalty {
+        match happy_visits {
+            0..=2 => Loyalty::Newcomer,
+            3..=7 => Loyalty::Regular,
+            _ => Loyalty::Loyal,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Loyalty::Newcomer => "Newcomer",
+            Loyalty::Regular => "Regular",
+            Loyalty::Loyal => "Loyal",
+        }
+    }
+
+    /// Scales the chance that they stop for a cup.
+    pub fn buy_multiplier(&self) -> f32 {
+        match self {
+            Loyalty::Newcomer => 1.1,
+            Loyalty::Regular => 1.25,
+            Loyalty::Loyal => 1.5,
+        }
+    }
+
+    /// Relative odds of being the regular who comes by next.
+    fn visit_weight(&self) -> f32 {
+        match self {
+            Loyalty::Newcomer => 1.0,
+            Loyalty::Regular => 2.0,
+            Loyalty::Loyal => 3.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Visit {
+    pub day: i32,
+    /// `None` when they left without a cup.
+    pub feedback: CustomerFeedback,
+}
+
+/// A customer the stand knows by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Regular {
+    pub name: String,
+    /// Resource path of their `CustomerVariant`.
+    pub variant: String,
+    pub segment: Segment,
+    pub joined_on: i32,
+    #[serde(default)]
+    pub visits: Vec<Visit>,
+    /// Disliked cups since the last one they liked.
+    #[serde(default)]
+    pub dislike_streak: i32,
+    /// Stopped coming after too many bad cups.
+    #[serde(default)]
+    pub lapsed: bool,
+}
+
+impl Regular {
+    pub fn new(name: &str, variant: &str, segment: Segment, day: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            variant: variant.to_string(),
+            segment,
+            joined_on: day,
+            visits: Vec::new(),
+            dislike_streak: 0,
+            lapsed: false,
+        }
+    }
+
+    pub fn happy_visits(&self) -> usize {
+        self.visits
+            .iter()
+            .filter(|visit| matches!(visit.feedback, CustomerFeedback::Love | CustomerFeedback::Like))
+            .count()
+    }
+
+    pub fn loyalty(&self) -> Loyalty {
+        Loyalty::of(self.happy_visits())
+    }
+
+    pub fn last_visit(&self) -> Option<i32> {
+        self.visits.last().map(|visit| visit.day)
+    }
+
+    /// Whether their last few cups went down better than the few before.
+    pub fn satisfaction_trend(&self) -> Trend {
+        let scores: Vec<f32> = self.visits
+            .iter()
+            .filter_map(|visit| match visit.feedback {
+                CustomerFeedback::Love => Some(1.0),
+                CustomerFeedback::Like => Some(0.6),
+                CustomerFeedback::Dislike => Some(0.0),
+                CustomerFeedback::None => None,
+            })
+            .collect();
+        if scores.len() < 2 {
+            return Trend::Steady;
+        }
+        let split = scores.len().saturating_sub(TREND_WINDOW).max(scores.len() / 2);
+        let (earlier, recent) = scores.split_at(split);
+        let earlier = &earlier[earlier.len().saturating_sub(TREND_WINDOW)..];
+        let average = |scores: &[f32]| scores.iter().sum::<f32>() / scores.len() as f32;
+        Trend::of(average(recent) - average(earlier))
+    }
+
+    pub fn record_visit(&mut self, day: i32, feedback: CustomerFeedback) {
+        match feedback {
+            CustomerFeedback::Love | CustomerFeedback::Like => self.dislike_streak = 0,
+            CustomerFeedback::Dislike => self.dislike_streak += 1,
+            CustomerFeedback::None => (),
+        }
+        if self.dislike_streak >= DISLIKE_STREAK_LIMIT {
+            self.lapsed = true;
+        }
+        self.visits.push(Visit { day, feedback });
+        if self.visits.len() > MAX_VISITS {
+            self.visits.drain(..self.visits.len() - MAX_VISITS);
+        }
+    }
+}
+
+/// Every regular the stand has had, lapsed ones included.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Roster {
+    pub regulars: Vec<Regular>,
+}
+
+impl Roster {
+    pub fn get(&self, name: &str) -> Option<&Regular> {
+        self.regulars.iter().find(|regular| regular.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Regular> {
+        self.regulars.iter_mut().find(|regular| regular.name == name)
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Regular> {
+        self.regulars.iter().filter(|regular| !regular.lapsed)
+    }
+
+    /// Picks an active regular who hasn't been by on `day`, loyal ones more
+    /// often. Only regulars whose variant is in `variants` can come.
+    pub fn pick_visitor(&self, day: i32, variants: &[String], rng: &mut impl SimRng) -> Option<&Regular> {
+        let due: Vec<&Regular> = self.active()
+            .filter(|regular| regular.last_visit() != Some(day) && variants.contains(&regular.variant))
+            .collect();
+        let weights: Vec<f32> = due.iter().map(|regular| regular.loyalty().visit_weight()).collect();
+        rng.pick_weighted(&weights).map(|index| due[index])
+    }
+
+    /// Adds a customer under a name no active regular has, unless the roster
+    /// is full. A lapsed regular whose name is reused is forgotten. Their
+    /// first visit is today's.
+    pub fn enlist(&mut self, variant: &str, segment: Segment, day: i32, feedback: CustomerFeedback, rng: &mut impl SimRng) -> Option<&Regular> {
+        if self.active().count() >= MAX_REGULARS {
+            return None;
+        }
+        let free: Vec<&str> = NAMES
+            .into_iter()
+            .filter(|name| self.get(name).is_none_or(|regular| regular.lapsed))
+            .collect();
+        if free.is_empty() {
+            return None;
+        }
+        let name = free[rng.randi_range(0, free.len() as i32 - 1) as usize];
+        self.regulars.retain(|regular| regular.name != name);
+
+        let mut regular = Regular::new(name, variant, segment, day);
+        regular.record_visit(day, feedback);
+        self.regulars.push(regular);
+        self.regulars.last()
+    }
+}
+
+#[test]
+fn loyalty_and_lapsing() {
+    // Test data
+    let mut regular = Regular::new("Adi", "res://resources/student_male.tres", Segment::Student, 1);
+
+    // Execute test
+    assert_eq!(regular.loyalty(), Loyalty::Newcomer);
+    for day in 1..=3 {
+        regular.record_visit(day, CustomerFeedback::Love);
+    }
+    regular.record_visit(4, CustomerFeedback::None);
+    assert_eq!(regular.loyalty(), Loyalty::Regular);
+    assert_eq!(regular.satisfaction_trend(), Trend::Steady);
+
+    regular.record_visit(5, CustomerFeedback::Dislike);
+    regular.record_visit(6, CustomerFeedback::Dislike);
+    assert_eq!(regular.satisfaction_trend(), Trend::Churning);
+    assert!(!regular.lapsed);
+    regular.record_visit(7, CustomerFeedback::Dislike);
+    assert!(regular.lapsed);
+    assert_eq!(regular.visits.len(), 7);
+}
+
+#[test]
+fn roster() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let mut roster = Roster::default();
+    let mut rng = SeededRng::new(4);
+    let variants = ["res://resources/student_male.tres".to_string()];
+
+    // Execute test
+    for _ in 0..NAMES.len() {
+        roster.enlist(&variants[0], Segment::Student, 1, CustomerFeedback::Love, &mut rng);
+    }
+    assert_eq!(roster.regulars.len(), MAX_REGULARS);
+    let names: Vec<&str> = roster.regulars.iter().map(|regular| regular.name.as_str()).collect();
+    assert!(names.iter().all(|name| names.iter().filter(|other| *other == name).count() == 1));
+
+    assert!(roster.pick_visitor(1, &variants, &mut rng).is_none());
+    assert!(roster.pick_visitor(2, &variants, &mut rng).is_some());
+    assert!(roster.pick_visitor(2, &["res://resources/old_person.tres".to_string()], &mut rng).is_none());
+}
+
+#[test]
+fn history_is_bounded() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let mut roster = Roster::default();
+    let mut rng = SeededRng::new(7);
+    let variant = "res://resources/old_person.tres";
+
+    // Execute test
+    for _ in 0..NAMES.len() {
+        roster.enlist(variant, Segment::Senior, 1, CustomerFeedback::Love, &mut rng);
+    }
+    for regular in roster.regulars.iter_mut().take(MAX_REGULARS) {
+        for day in 2..=4 {
+            regular.record_visit(day, CustomerFeedback::Dislike);
+        }
+    }
+    assert_eq!(roster.active().count(), 0);
+    for _ in 0..NAMES.len() {
+        roster.enlist(variant, Segment::Senior, 5, CustomerFeedback::Love, &mut rng);
+    }
+    assert_eq!(roster.active().count(), MAX_REGULARS);
+    let names: Vec<&str> = roster.regulars.iter().map(|regular| regular.name.as_str()).collect();
+    assert!(names.iter().all(|name| names.iter().filter(|other| *other == name).count() == 1));
+
+    let regular = &mut roster.regulars[0];
+    for day in 6..6 + MAX_VISITS as i32 {
+        regular.record_visit(day, CustomerFeedback::Like);
+    }
+    assert_eq!(regular.visits.len(), MAX_VISITS);
+    assert_eq!(regular.visits[0].day, 6);
+    assert_eq!(regular.loyalty(), Loyalty::Loyal);
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::customer_feedback::CustomerFeedback, sim::customer::Segment};
+
+/// Where every segment starts in a new run.
+pub const STARTING_REPUTATION: f32 = 0.5;
+/// Share of a verdict that spreads by word of mouth to everyone.
+pub const BUZZ_SHARE: f32 = 0.25;
+/// Buzz left after a night.
+pub const BUZZ_RETENTION: f32 = 0.5;
+pub const MAX_BUZZ: f32 = 0.2;
+/// Hit to a segment when one of them gives up waiting, a bit worse than a
+/// disliked cup.
+pub const ABANDON_PENALTY: f32 = 0.05;
+/// Change over a day above which a segment counts as growing or churning.
+pub const TREND_THRESHOLD: f32 = 0.01;
+
+/// How the stand is seen: one lasting value per segment, from 0 to 1, and a
+/// short-lived buzz every segment shares.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reputation {
+    pub segments: BTreeMap<Segment, f32>,
+    pub buzz: f32,
+}
+
+impl Default for Reputation {
+    fn default() -> Self {
+        Self::uniform(STARTING_REPUTATION)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Growing,
+    Steady,
+    Churning,
+}
+
+impl Trend {
+    pub fn of(change: f32) -> Trend {
+        if change > TREND_THRESHOLD {
+            Trend::Growing
+        } else if change < -TREND_THRESHOLD {
+            Trend::Churning
+        } else {
+            Trend::Steady
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Trend::Growing => "growing",
+            Trend::Steady => "steady",
+            Trend::Churning => "churning",
+        }
+    }
+}
+
+impl Reputation {
+    /// Every segment at `value`, no buzz.
+    pub fn uniform(value: f32) -> Self {
+        Self {
+            segments: Segment::ALL.iter().map(|segment| (*segment, value.clamp(0.0, 1.0))).collect(),
+            buzz: 0.0,
+        }
+    }
+
+    /// The segment's own standing, without buzz.
+    pub fn standing(&self, segment: Segment) -> f32 {
+        self.segments.get(&segment).copied().unwrap_or(STARTING_REPUTATION)
+    }
+
+    /// What a customer of `segment` thinks of the stand right now.
+    pub fn of(&self, segment: Segment) -> f32 {
+        (self.standing(segment) + self.buzz).clamp(0.0, 1.0)
+    }
+
+    /// Average over all segments, buzz included. Used where the whole
+    /// business is judged, like the lender's credit limit.
+    pub fn overall(&self) -> f32 {
+        Segment::ALL.iter().map(|segment| self.of(*segment)).sum::<f32>() / Segment::ALL.len() as f32
+    }
+
+    /// Moves the customer's segment with their verdict, scaled by `gain` when
+    /// it's a good one, and spreads a share of it as buzz.
+    pub fn record_feedback(&mut self, segment: Segment, feedback: &CustomerFeedback, gain: f32) {
+        let delta = match feedback {
+            CustomerFeedback::Love => 0.05 * gain,
+            CustomerFeedback::Like => 0.02 * gain,
+            CustomerFeedback::Dislike => -0.04,
+            CustomerFeedback::None => 0.0,
+        };
+        self.spread(segment, delta);
+    }
+
+    /// A customer left the line without their order, which people notice.
+    pub fn record_abandonment(&mut self, segment: Segment) {
+        self.spread(segment, -ABANDON_PENALTY);
+    }
+
+    /// Lowers every segment, e.g. when word gets out about missed payments.
+    pub fn penalize(&mut self, amount: f32) {
+        for segment in Segment::ALL {
+            self.shift(segment, -amount);
+        }
+    }
+
+    /// Buzz fades overnight.
+    pub fn end_day(&mut self) {
+        self.buzz *= BUZZ_RETENTION;
+    }
+
+    /// Moves the segment and passes a share on as buzz.
+    fn spread(&mut self, segment: Segment, delta: f32) {
+        self.shift(segment, delta);
+        self.buzz = (self.buzz + delta * BUZZ_SHARE).clamp(-MAX_BUZZ, MAX_BUZZ);
+    }
+
+    fn shift(&mut self, segment: Segment, delta: f32) {
+        let value = (self.standing(segment) + delta).clamp(0.0, 1.0);
+        self.segments.insert(segment, value);
+    }
+}
+
+#[test]
+fn segments_move_apart() {
+    // Test data
+    let mut reputation = Reputation::default();
+
+    // Execute test
+    for _ in 0..4 {
+        reputation.record_feedback(Segment::Student, &CustomerFeedback::Love, 1.0);
+        reputation.record_feedback(Segment::OfficeWorker, &CustomerFeedback::Dislike, 1.0);
+    }
+    assert!((reputation.standing(Segment::Student) - 0.7).abs() < 1e-5);
+    assert!((reputation.standing(Segment::OfficeWorker) - 0.34).abs() < 1e-5);
+    assert_eq!(reputation.standing(Segment::Senior), STARTING_REPUTATION);
+    assert!((reputation.buzz - 0.01).abs() < 1e-5);
+    assert!((reputation.of(Segment::Senior) - 0.51).abs() < 1e-5);
+
+    reputation.end_day();
+    assert!((reputation.buzz - 0.005).abs() < 1e-5);
+
+    let before = Reputation::default();
+    assert_eq!(Trend::of(reputation.standing(Segment::Student) - before.standing(Segment::Student)), Trend::Growing);
+    assert_eq!(Trend::of(reputation.standing(Segment::OfficeWorker) - before.standing(Segment::OfficeWorker)), Trend::Churning);
+    assert_eq!(Trend::of(0.0), Trend::Steady);
+}
+
+#[test]
+fn dislike_lowers_standing() {
+    // Test data
+    let mut reputation = Reputation::default();
+
+    // Execute test
+    reputation.record_feedback(Segment::Senior, &CustomerFeedback::Dislike, 2.0);
+    assert!((reputation.standing(Segment::Senior) - 0.46).abs() < 1e-5);
+    assert!(reputation.buzz < 0.0);
+}
@@ -0,0 +1,99 @@
+/// Source of randomness for the simulation.
+///
+/// The game plugs in Godot's `RandomNumberGenerator`, tests use [`SeededRng`]
+/// so every roll is reproducible.
+pub trait SimRng {
+    /// Uniform float in `[0, 1)`.
+    fn randf(&mut self) -> f32;
+
+    fn randf_range(&mut self, from: f32, to: f32) -> f32 {
+        from + (to - from) * self.randf()
+    }
+
+    /// Uniform integer in `[from, to]`.
+    fn randi_range(&mut self, from: i32, to: i32) -> i32 {
+        if to <= from {
+            return from;
+        }
+        let span = (to - from + 1) as f32;
+        from + ((self.randf() * span) as i32).min(to - from)
+    }
+
+    fn check_chance(&mut self, percent: f32) -> bool {
+        self.randf() < (percent / 100.0)
+    }
+
+    /// Index picked with odds proportional to `weights`, `None` when no
+    /// weight is positive.
+    fn pick_weighted(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().filter(|weight| **weight > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = self.randf() * total;
+        for (index, weight) in weights.iter().enumerate().filter(|(_, weight)| **weight > 0.0) {
+            if roll < *weight {
+                return Some(index);
+            }
+            roll -= weight;
+        }
+        weights.iter().rposition(|weight| *weight > 0.0)
+    }
+}
+
+/// Small deterministic generator (SplitMix64).
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generator for one `stream` of a run on a given day. Reloading a save
+    /// replays the same rolls, and streams don't disturb each other.
+    pub fn for_day(seed: u64, day: i32, stream: u64) -> Self {
+        let mut rng = Self::new(seed ^ stream.wrapping_mul(0xD6E8_FEB8_6659_FD93));
+        rng.state ^= rng.next_u64().wrapping_add(day as u64);
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl SimRng for SeededRng {
+    fn randf(&mut self) -> f32 {
+        // 24 random bits fit exactly in an f32 mantissa
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[test]
+fn seeded_rng_is_reproducible() {
+    // Test data
+    let mut a = SeededRng::new(42);
+    let mut b = SeededRng::new(42);
+
+    // Execute test
+    for _ in 0..100 {
+        let roll = a.randf();
+        assert_eq!(roll, b.randf());
+        assert!((0.0..1.0).contains(&roll));
+    }
+    for _ in 0..100 {
+        let roll = a.randi_range(3, 5);
+        assert!((3..=5).contains(&roll));
+    }
+    for _ in 0..100 {
+        assert_eq!(a.pick_weighted(&[0.0, 2.0, -1.0]), Some(1));
+    }
+    assert_eq!(a.pick_weighted(&[0.0, 0.0]), None);
+}
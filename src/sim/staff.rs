@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::customer_feedback::CustomerFeedback, sim::{recipe::CoffeeComponent, rng::SimRng}};
+
+/// Room behind the counter next to the owner.
+pub const MAX_STAFF: usize = 3;
+/// Share an amount can be off by when someone with no skill makes a drink.
+pub const MAX_VARIANCE: f32 = 0.4;
+/// Who serves when nobody is hired.
+pub const OWNER: &str = "You";
+const CANDIDATES_PER_DAY: usize = 3;
+const NAMES: [&str; 12] = ["Ayu", "Budi", "Citra", "Dewi", "Eko", "Fajar", "Gita", "Hadi", "Intan", "Joko", "Kiki", "Lestari"];
+
+/// Someone working the counter for a daily wage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaffMember {
+    pub name: String,
+    /// Paid at closing, see [`GameState::pay_expenses`](crate::sim::state::GameState::pay_expenses).
+    pub wage: i32,
+    /// Multiplies the cart's serving speed.
+    pub speed: f32,
+    /// 0 to 1, how close their drinks come to the recipe.
+    pub skill: f32,
+    #[serde(default)]
+    pub hired_on: i32,
+}
+
+impl StaffMember {
+    pub fn new(name: &str, wage: i32, speed: f32, skill: f32) -> Self {
+        Self { name: name.to_string(), wage, speed, skill, hired_on: 0 }
+    }
+
+    /// The player, always at the counter and always on recipe.
+    pub fn owner() -> Self {
+        Self::new(OWNER, 0, 1.0, 1.0)
+    }
+
+    /// A random applicant. Faster and more skilled people ask for more.
+    pub fn generate(name: &str, rng: &mut impl SimRng) -> Self {
+        let speed = rng.randf_range(0.6, 1.4);
+        let skill = rng.randf_range(0.3, 1.0);
+        let wage = (10.0 + 12.0 * speed + 20.0 * skill).round() as i32;
+        Self::new(name, wage, speed, skill)
+    }
+
+    /// The drink as this person actually makes it, every amount off by up
+    /// to [`MAX_VARIANCE`] scaled by how unskilled they are.
+    pub fn make(&self, recipe: &CoffeeComponent, rng: &mut impl SimRng) -> CoffeeComponent {
+        let variance = MAX_VARIANCE * (1.0 - self.skill.clamp(0.0, 1.0));
+        recipe
+            .iter()
+            .map(|(id, amount)| (id, amount * rng.randf_range(1.0 - variance, 1.0 + variance)))
+            .collect()
+    }
+}
+
+/// Today's applicants, all with different names.
+pub fn candidates(rng: &mut impl SimRng) -> Vec<StaffMember> {
+    let mut names = NAMES.to_vec();
+    (0..CANDIDATES_PER_DAY)
+        .map(|_| {
+            let name = names.remove(rng.randi_range(0, names.len() as i32 - 1) as usize);
+            StaffMember::generate(name, rng)
+        })
+        .collect()
+}
+
+/// What one person did over a day, or over their whole time at the stand.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaffShift {
+    pub orders: i32,
+    pub cups: i32,
+    pub love: i32,
+    pub like: i32,
+    pub dislike: i32,
+    pub wage: i32,
+}
+
+impl StaffShift {
+    pub fn record_order(&mut self, cups: i32, feedback: &CustomerFeedback) {
+        self.orders += 1;
+        self.cups += cups;
+        match feedback {
+            CustomerFeedback::Love => self.love += 1,
+            CustomerFeedback::Like => self.like += 1,
+            CustomerFeedback::Dislike => self.dislike += 1,
+            CustomerFeedback::None => (),
+        }
+    }
+
+    pub fn merge(&mut self, other: &StaffShift) {
+        self.orders += other.orders;
+        self.cups += other.cups;
+        self.love += other.love;
+        self.like += other.like;
+        self.dislike += other.dislike;
+        self.wage += other.wage;
+    }
+
+    /// Share of served orders the customers loved or liked.
+    pub fn satisfaction(&self) -> Option<f32> {
+        let rated = self.love + self.like + self.dislike;
+        if rated == 0 {
+            return None;
+        }
+        Some((self.love + self.like) as f32 / rated as f32)
+    }
+}
+
+#[test]
+fn skill_sets_variance() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let recipe = CoffeeComponent::from([("coffee", 10.0), ("milk", 100.0)]);
+    let barista = StaffMember::new("Ayu", 40, 1.0, 1.0);
+    let trainee = StaffMember::new("Budi", 15, 1.0, 0.0);
+    let mut rng = SeededRng::new(3);
+
+    // Execute test
+    assert_eq!(barista.make(&recipe, &mut rng), recipe);
+    for _ in 0..100 {
+        let made = trainee.make(&recipe, &mut rng);
+        assert!((6.0..=14.0).contains(&made.get("coffee")));
+        assert!((60.0..=140.0).contains(&made.get("milk")));
+    }
+    assert_ne!(trainee.make(&recipe, &mut rng), recipe);
+
+    let applicants = candidates(&mut SeededRng::new(8));
+    assert_eq!(applicants.len(), CANDIDATES_PER_DAY);
+    assert!(applicants.iter().all(|applicant| applicants.iter().filter(|other| other.name == applicant.name).count() == 1));
+}
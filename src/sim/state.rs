@@ -0,0 +1,918 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::customer_feedback::CustomerFeedback, sim::{campaign::{ActiveCampaign, CampaignList}, catalog::Product, customer::{DrinkChoice, Segment}, expenses, ingredient::IngredientRegistry, inventory::Inventory, ledger::{DayLedger, Ledger, TransactionKind}, loan::{self, Loan}, market::{Market, CUP}, menu::Menu, recipe::CoffeeComponent, regulars::{self, Roster}, reputation::Reputation, rng::{SeededRng, SimRng}, staff::{self, StaffMember}, upgrade::{Modifiers, Stat, Upgrade, UpgradeTree}, weather::Weather}};
+
+/// Ledger item for unsold cups of any drink.
+pub const PREPARED_CUP: &str = "prepared cup";
+
+/// Random streams, see [`SeededRng::for_day`].
+const MARKET_STREAM: u64 = 1;
+const EXPENSES_STREAM: u64 = 2;
+const WEATHER_STREAM: u64 = 3;
+const STAFF_STREAM: u64 = 4;
+
+/// Everything that describes a run, independent of any scene.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GameState {
+    pub money: i32,
+    pub day: i32,
+    pub cup: i32,
+    pub reputation: Reputation,
+    pub inventory: Inventory,
+    pub menu: Menu,
+    #[serde(default)]
+    pub ledger: Ledger,
+    /// Seed of the run, every daily roll derives from it.
+    #[serde(default)]
+    pub seed: u64,
+    #[serde(default)]
+    pub market: Market,
+    /// Days in a row the run closed with negative money.
+    #[serde(default)]
+    pub days_in_debt: i32,
+    #[serde(default)]
+    pub loans: Vec<Loan>,
+    /// Today's weather, revealed when the stand opens.
+    #[serde(default)]
+    pub weather: Weather,
+    /// What the prep phase tells the player to expect today.
+    #[serde(default)]
+    pub forecast: Weather,
+    /// Ids of the cart upgrades bought so far.
+    #[serde(default)]
+    pub upgrades: Vec<String>,
+    /// Hired help, the owner not included.
+    #[serde(default)]
+    pub staff: Vec<StaffMember>,
+    #[serde(default)]
+    pub campaigns: Vec<ActiveCampaign>,
+    #[serde(default)]
+    pub roster: Roster,
+    /// Game data rather than progress, set by the engine after loading.
+    #[serde(skip)]
+    pub ingredients: IngredientRegistry,
+    #[serde(skip)]
+    pub upgrade_tree: UpgradeTree,
+    #[serde(skip)]
+    pub campaign_list: CampaignList,
+}
+
+impl GameState {
+    pub fn new_game() -> Self {
+        let mut state = Self::default();
+        state.start_new(0);
+        state
+    }
+
+    pub fn start_new(&mut self, seed: u64) {
+        self.money = 300;
+        self.day = 1;
+        self.inventory = Inventory::with(&CoffeeComponent::from([("coffee", 300.0), ("milk", 1000.0), ("sugar", 1000.0)]), self.day);
+        self.menu = Menu::default();
+        self.cup = 50;
+        self.reputation = Reputation::default();
+        self.ledger = Ledger::default();
+        self.seed = seed;
+        self.market = Market::default();
+        self.days_in_debt = 0;
+        self.loans = Vec::new();
+        self.upgrades = Vec::new();
+        self.staff = Vec::new();
+        self.campaigns = Vec::new();
+        self.roster = Roster::default();
+        self.roll_weather();
+    }
+
+    pub fn is_new_game(&self) -> bool {
+        self.day == 0
+    }
+
+    /// Most cups of `recipe` that fit next to what the other drinks already use.
+    pub fn max_servings(&self, drink: usize, recipe: &CoffeeComponent) -> i32 {
+        let mut available = self.inventory.totals();
+        available.subtract(&self.menu.usage_without(Some(drink)));
+        let other_cups: i32 = self.menu.total_stock() - self.menu.item(drink).map_or(0, |item| item.stock);
+        available.servings_of(recipe).min(self.cup.min(self.stock_capacity()) - other_cups).max(0)
+    }
+
+    /// Everything that currently changes the stand's stats.
+    pub fn modifiers(&self) -> Modifiers {
+        let mut modifiers = Modifiers::default();
+        modifiers.extend(self.upgrade_tree.modifiers(&self.upgrades));
+        for campaign in self.campaigns.iter().filter_map(|active| self.campaign_list.get(&active.id)) {
+            modifiers.extend(campaign.modifiers.iter());
+        }
+        modifiers
+    }
+
+    /// Most cups all drinks together can be prepared for a day.
+    pub fn stock_capacity(&self) -> i32 {
+        self.modifiers().value(Stat::StockCapacity).floor() as i32
+    }
+
+    pub fn owns_upgrade(&self, id: &str) -> bool {
+        self.upgrades.iter().any(|owned| owned == id)
+    }
+
+    /// The upgrade if the player can buy it right now, or why not.
+    pub fn check_upgrade(&self, id: &str) -> Result<&Upgrade, String> {
+        let Some(upgrade) = self.upgrade_tree.get(id) else {
+            return Err(format!("Unknown upgrade {}", id));
+        };
+        if self.owns_upgrade(id) {
+            return Err(format!("{} is already installed", upgrade.name));
+        }
+        if let Some(required) = upgrade.requires.as_deref().filter(|required| !self.owns_upgrade(required)) {
+            let name = self.upgrade_tree.get(required).map_or(required, |required| required.name.as_str());
+            return Err(format!("{} needs the {} first", upgrade.name, name));
+        }
+        if self.money < upgrade.price {
+            return Err("Not enough money".to_string());
+        }
+        Ok(upgrade)
+    }
+
+    /// Pays for an upgrade and installs it. Returns the amount paid.
+    pub fn buy_upgrade(&mut self, id: &str) -> Result<i32, String> {
+        let (name, price) = self.check_upgrade(id).map(|upgrade| (upgrade.name.clone(), upgrade.price))?;
+        self.add_money(-price);
+        self.upgrades.push(id.to_string());
+        self.today().record(TransactionKind::Upgrade, &name, 1.0, -price);
+        Ok(price)
+    }
+
+    /// Updates a drink and prepares `cups` of it, or as many as ingredients,
+    /// cups and the stand's capacity allow when `cups` is `None`. Returns the cups prepared.
+    pub fn prepare_drink(&mut self, drink: usize, recipe: CoffeeComponent, price: i32, cups: Option<i32>) -> i32 {
+        let max = self.max_servings(drink, &recipe);
+        let Some(item) = self.menu.items.get_mut(drink) else {
+            return 0;
+        };
+        item.recipe = recipe;
+        item.price = price;
+        item.stock = cups.map_or(max, |cups| cups.clamp(0, max));
+        item.stock
+    }
+
+    pub fn today(&mut self) -> &mut DayLedger {
+        self.ledger.day_mut(self.day)
+    }
+
+    /// Adds a fresh lot bought today.
+    pub fn stock_up(&mut self, ingredient: &str, quantity: f32) {
+        self.inventory.add(ingredient, quantity, self.day);
+    }
+
+    /// Total cost of a shop order at today's prices, or why the supplier
+    /// can't fill it.
+    pub fn quote_order(&self, order: &[(Product, i32)]) -> Result<i32, String> {
+        let mut total = 0;
+        let mut packs_per_good: BTreeMap<&str, i32> = BTreeMap::new();
+        for (product, packs) in order.iter().filter(|(_, packs)| *packs > 0) {
+            if !product.is_unlocked(self.day) {
+                return Err(format!("{} is not available yet", product.name));
+            }
+            if product.good != CUP && !self.ingredients.contains(&product.good) {
+                return Err(format!("{} is not an ingredient the shop uses", product.name));
+            }
+            *packs_per_good.entry(&product.good).or_default() += packs;
+            total += self.market.quote(product, *packs);
+        }
+        for (good, packs) in packs_per_good {
+            if let Some(available) = self.market.packs_available(good) {
+                if packs > available {
+                    let name = self.ingredients.name(good).to_lowercase();
+                    return Err(format!("Shortage: only {} pack(s) of {} left today", available, name));
+                }
+            }
+        }
+        Ok(total)
+    }
+
+    /// Pays for a shop order and stocks today's lots. Returns the amount paid.
+    pub fn buy_order(&mut self, order: &[(Product, i32)]) -> Result<i32, String> {
+        let total = self.quote_order(order)?;
+        if self.money < total {
+            return Err(match self.available_credit() {
+                0 => "Not enough money".to_string(),
+                credit => format!("Not enough money. The lender offers up to {} in the Loans tab.", credit),
+            });
+        }
+
+        for (product, packs) in order.iter().filter(|(_, packs)| *packs > 0) {
+            let cost = self.market.quote(product, *packs);
+            let quantity = *packs as f32 * product.quantity;
+            if product.good == CUP {
+                self.cup += quantity as i32;
+            } else {
+                self.stock_up(&product.good, quantity);
+            }
+            self.market.buy(&product.good, *packs);
+            self.add_money(-cost);
+            self.record_purchase(&product.good, quantity, cost);
+        }
+        Ok(total)
+    }
+
+    /// Logs supplies bought from the shop. The caller pays for them.
+    pub fn record_purchase(&mut self, item: &str, quantity: f32, cost: i32) {
+        self.today().record(TransactionKind::Purchase, item, quantity, -cost);
+    }
+
+    /// Consumes the ingredients, oldest lots first, and cups for the prepared stock.
+    pub fn start_day(&mut self) {
+        let stock = self.menu.total_stock();
+        self.cup -= stock;
+        let used = self.menu.usage_without(None);
+        self.inventory.consume_recipe(&used);
+
+        let reputation = self.reputation.clone();
+        let today = self.today();
+        today.cups_prepared += stock;
+        today.reputation_start = reputation;
+    }
+
+    /// Takes up to `amount` cups of a drink out of stock and returns how many
+    /// there were. Cups that weren't there count as short, whoever ordered them.
+    pub fn take_order(&mut self, drink: usize, amount: i32) -> i32 {
+        let Some(item) = self.menu.items.get_mut(drink) else {
+            return 0;
+        };
+        let taken = amount.min(item.stock).max(0);
+        item.stock -= taken;
+        self.today().cups_short += amount - taken;
+        taken
+    }
+
+    /// A party left the line before being served: the `taken` cups go back
+    /// on the menu and each member's segment thinks a little less of the
+    /// stand. All `ordered` cups count as given up on, none as short.
+    pub fn abandon_order(&mut self, segments: &[Segment], drink: usize, taken: i32, ordered: i32) {
+        if let Some(item) = self.menu.items.get_mut(drink) {
+            item.stock += taken;
+        }
+        let today = self.today();
+        today.abandoned += ordered;
+        today.cups_short -= ordered - taken;
+        for segment in segments {
+            self.reputation.record_abandonment(*segment);
+        }
+    }
+
+    /// Pays for `amount` cups of a drink at its menu price and returns the money earned.
+    pub fn sell(&mut self, drink: usize, amount: i32) -> i32 {
+        let Some(item) = self.menu.item(drink).filter(|_| amount > 0) else {
+            return 0;
+        };
+        let (name, paid) = (item.name.clone(), amount * item.price);
+        self.add_money(paid);
+
+        let today = self.today();
+        today.record(TransactionKind::Sale, &name, amount as f32, paid);
+        today.cups_sold += amount;
+        paid
+    }
+
+    pub fn add_money(&mut self, amount: i32) -> i32 {
+        self.money += amount;
+        self.money
+    }
+
+    /// Logs what a customer at the stand decided on.
+    pub fn record_choice(&mut self, choice: &DrinkChoice) {
+        match *choice {
+            DrinkChoice::Order(drink) => {
+                let Some(name) = self.menu.item(drink).map(|item| item.name.clone()) else {
+                    return;
+                };
+                self.today().record_order(&name);
+            }
+            DrinkChoice::WalkAway(reason) => self.today().record_walk_away(reason),
+        }
+    }
+
+    /// Counts the customer's verdict and moves their segment's reputation with it.
+    pub fn record_feedback(&mut self, segment: Segment, feedback: &CustomerFeedback) {
+        self.today().record_feedback(feedback);
+        let gain = self.modifiers().value(Stat::ReputationGain);
+        self.reputation.record_feedback(segment, feedback, gain);
+    }
+
+    /// Charges today's running costs and staff wages. Money may go
+    /// negative, see [`GameState::is_bankrupt`]. Returns the total charged.
+    pub fn pay_expenses(&mut self) -> i32 {
+        let mut rng = SeededRng::for_day(self.seed, self.day, EXPENSES_STREAM);
+        let mut total = 0;
+        for expense in expenses::due_expenses(self.day, &mut rng) {
+            self.add_money(-expense.amount);
+            self.today().record(TransactionKind::Expense, expense.name, 1.0, -expense.amount);
+            total += expense.amount;
+        }
+        for member in self.staff.clone() {
+            self.add_money(-member.wage);
+            let today = self.today();
+            today.record(TransactionKind::Expense, &format!("wage: {}", member.name), 1.0, -member.wage);
+            today.shift_mut(&member.name).wage += member.wage;
+            total += member.wage;
+        }
+
+        if self.money < 0 {
+            self.days_in_debt += 1;
+        } else {
+            self.days_in_debt = 0;
+        }
+        total
+    }
+
+    /// Days the stand can still close in debt without going bankrupt.
+    pub fn grace_days_left(&self) -> i32 {
+        (expenses::GRACE_DAYS - self.days_in_debt).max(0)
+    }
+
+    pub fn is_bankrupt(&self) -> bool {
+        self.days_in_debt > expenses::GRACE_DAYS
+    }
+
+    /// Everything still owed to the lender.
+    pub fn outstanding_debt(&self) -> i32 {
+        self.loans.iter().map(|loan| loan.balance).sum()
+    }
+
+    pub fn available_credit(&self) -> i32 {
+        (loan::credit_limit(self.reputation.overall(), self.day) - self.outstanding_debt()).max(0)
+    }
+
+    pub fn take_loan(&mut self, amount: i32) -> Result<(), String> {
+        if amount <= 0 {
+            return Err("Enter the amount to borrow".to_string());
+        }
+        let available = self.available_credit();
+        if amount > available {
+            return Err(format!("The lender only offers up to {} right now", available));
+        }
+
+        self.loans.push(Loan::new(amount));
+        self.add_money(amount);
+        self.today().record(TransactionKind::Loan, "loan", 1.0, amount);
+        Ok(())
+    }
+
+    /// Pays back up to `amount` early, oldest loan first. Returns the amount paid.
+    pub fn repay_loans(&mut self, amount: i32) -> i32 {
+        let mut left = amount.min(self.money).max(0);
+        let mut paid = 0;
+        for loan in self.loans.iter_mut() {
+            let payment = left.min(loan.balance);
+            loan.balance -= payment;
+            left -= payment;
+            paid += payment;
+        }
+        self.loans.retain(|loan| !loan.is_paid_off());
+
+        if paid > 0 {
+            self.add_money(-paid);
+            self.today().record(TransactionKind::Loan, "repayment", 1.0, -paid);
+        }
+        paid
+    }
+
+    /// Adds a day of interest and collects today's installments. Returns the
+    /// number of installments that couldn't be paid.
+    pub fn service_loans(&mut self) -> i32 {
+        let mut paid = 0;
+        let mut missed = 0;
+        for loan in self.loans.iter_mut() {
+            loan.accrue_interest();
+            let installment = loan.installment();
+            if self.money - paid >= installment {
+                loan.balance -= installment;
+                paid += installment;
+            } else {
+                loan.balance += loan::LATE_FEE;
+                loan.missed_payments += 1;
+                missed += 1;
+            }
+            loan.days_left = (loan.days_left - 1).max(0);
+        }
+        self.loans.retain(|loan| !loan.is_paid_off());
+
+        if paid > 0 {
+            self.add_money(-paid);
+            self.today().record(TransactionKind::Loan, "installment", 1.0, -paid);
+        }
+        let penalty = missed as f32 * loan::MISSED_PAYMENT_PENALTY;
+        self.reputation.penalize(penalty);
+        missed
+    }
+
+    /// Pays for a campaign that starts running today. Returns the amount paid.
+    pub fn launch_campaign(&mut self, id: &str) -> Result<i32, String> {
+        let Some(campaign) = self.campaign_list.get(id).cloned() else {
+            return Err(format!("Unknown campaign {}", id));
+        };
+        if let Some(active) = self.campaigns.iter().find(|active| active.id == id) {
+            return Err(format!("{} is already running for {} more day(s)", campaign.name, active.days_left));
+        }
+        if self.money < campaign.cost {
+            return Err("Not enough money".to_string());
+        }
+
+        self.add_money(-campaign.cost);
+        self.campaigns.push(ActiveCampaign { id: id.to_string(), days_left: campaign.days });
+        self.today().record(TransactionKind::Expense, &format!("campaign: {}", campaign.name), 1.0, -campaign.cost);
+        Ok(campaign.cost)
+    }
+
+    /// Everyone at the counter today, the owner first.
+    pub fn workers(&self) -> Vec<StaffMember> {
+        std::iter::once(StaffMember::owner()).chain(self.staff.iter().cloned()).collect()
+    }
+
+    /// Today's applicants who haven't been hired yet.
+    pub fn candidates(&self) -> Vec<StaffMember> {
+        let mut rng = SeededRng::for_day(self.seed, self.day, STAFF_STREAM);
+        staff::candidates(&mut rng)
+            .into_iter()
+            .filter(|candidate| self.staff.iter().all(|member| member.name != candidate.name))
+            .collect()
+    }
+
+    pub fn hire(&mut self, name: &str) -> Result<(), String> {
+        if self.staff.len() >= staff::MAX_STAFF {
+            return Err(format!("There is only room for {} staff behind the counter", staff::MAX_STAFF));
+        }
+        let Some(mut member) = self.candidates().into_iter().find(|candidate| candidate.name == name) else {
+            return Err(format!("{} is not looking for work today", name));
+        };
+        member.hired_on = self.day;
+        self.staff.push(member);
+        Ok(())
+    }
+
+    /// Lets someone go. They aren't paid for today.
+    pub fn fire(&mut self, name: &str) -> bool {
+        let count = self.staff.len();
+        self.staff.retain(|member| member.name != name);
+        self.staff.len() < count
+    }
+
+    /// Credits an order to whoever served it.
+    pub fn record_serve(&mut self, worker: &str, cups: i32, feedback: &CustomerFeedback) {
+        self.today().shift_mut(worker).record_order(cups, feedback);
+    }
+
+    /// Adds today's visit to a regular's history.
+    pub fn record_visit(&mut self, regular: &str, feedback: CustomerFeedback) {
+        let day = self.day;
+        if let Some(regular) = self.roster.get_mut(regular) {
+            regular.record_visit(day, feedback);
+        }
+    }
+
+    /// A customer who loved their cup may come back as a regular. Returns
+    /// the name they go by from now on.
+    pub fn maybe_enlist(&mut self, variant: &str, segment: Segment, feedback: CustomerFeedback, rng: &mut impl SimRng) -> Option<String> {
+        if feedback != CustomerFeedback::Love || !rng.check_chance(regulars::JOIN_CHANCE) {
+            return None;
+        }
+        let day = self.day;
+        self.roster.enlist(variant, segment, day, feedback, rng).map(|regular| regular.name.clone())
+    }
+
+    fn roll_weather(&mut self) {
+        let mut rng = SeededRng::for_day(self.seed, self.day, WEATHER_STREAM);
+        self.weather = Weather::roll(&mut rng);
+        self.forecast = Weather::forecast(self.weather, &mut rng);
+    }
+
+    /// Closes the day: unsold cups and ingredients past their shelf life are
+    /// thrown away and the calendar moves on.
+    pub fn end_day(&mut self) {
+        let (stock, reputation) = (self.menu.total_stock(), self.reputation.clone());
+        let expired = self.inventory.remove_expired(self.day + 1, &self.ingredients);
+        let today = self.today();
+        if stock > 0 {
+            today.record(TransactionKind::Waste, PREPARED_CUP, stock as f32, 0);
+        }
+        for lot in expired {
+            today.record(TransactionKind::Waste, &lot.ingredient, lot.quantity, 0);
+        }
+        today.reputation_end = reputation;
+
+        self.day += 1;
+        self.menu.clear_stock();
+        self.reputation.end_day();
+        for campaign in self.campaigns.iter_mut() {
+            campaign.days_left -= 1;
+        }
+        self.campaigns.retain(|campaign| campaign.days_left > 0);
+
+        let mut rng = SeededRng::for_day(self.seed, self.day, MARKET_STREAM);
+        let goods = self.ingredients.ids().chain([CUP]);
+        self.market.advance(goods, &mut rng);
+        self.roll_weather();
+    }
+}
+
+#[test]
+fn prepare_drink() {
+    // Test data
+    let inventory = CoffeeComponent::from([
+        ("coffee", 300.0), // grams
+        ("milk", 1000.0), // mL
+        ("sugar", 1000.0), // grams
+    ]);
+    let cup: i32 = 50;
+
+    let recipe = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]);
+
+    // Expected values
+    let expected_stock = 8;
+    let expected_cup = 42;
+
+    // Execute test
+    let mut state = GameState { inventory: Inventory::with(&inventory, 1), cup, ..Default::default() };
+    assert_eq!(state.prepare_drink(0, recipe, 8, None), expected_stock);
+    assert_eq!(state.menu.total_stock(), expected_stock);
+
+    state.start_day();
+    assert_eq!(state.cup, expected_cup);
+    assert_eq!(state.inventory.totals(), CoffeeComponent::from([("coffee", 244.0), ("milk", 40.0), ("sugar", 920.0)]));
+}
+
+#[test]
+fn prepare_drink_is_limited_by_cups() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.cup = 3;
+
+    // Execute test
+    assert_eq!(state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, None), 3);
+    assert_eq!(state.prepare_drink(1, CoffeeComponent::from([("coffee", 9.0)]), 6, Some(2)), 0);
+    assert_eq!(state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, Some(1)), 1);
+    assert_eq!(state.prepare_drink(1, CoffeeComponent::from([("coffee", 9.0)]), 6, Some(5)), 2);
+}
+
+#[test]
+fn drinks_share_ingredients() {
+    // Test data
+    let mut state = GameState::new_game();
+    let kopi_susu = CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]);
+    let black = CoffeeComponent::from([("coffee", 9.0)]);
+
+    // Execute test
+    assert_eq!(state.prepare_drink(0, kopi_susu, 8, Some(5)), 5);
+    assert_eq!(state.max_servings(1, &black), (300 - 35) / 9);
+    assert_eq!(state.prepare_drink(1, black, 6, None), 29);
+
+    state.start_day();
+    assert_eq!(state.cup, 50 - 34);
+    assert_eq!(state.inventory.total("coffee"), 300.0 - 35.0 - 261.0);
+}
+
+#[test]
+fn day_loop() {
+    use crate::sim::{customer::CustomerProfile, rng::SeededRng};
+
+    // Test data
+    let mut state = GameState::new_game();
+    let mut rng = SeededRng::new(7);
+    let profile = CustomerProfile::new(CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 10);
+
+    // Execute test
+    state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, None);
+    state.start_day();
+    let prepared = state.menu.total_stock();
+
+    let mut sold = 0;
+    for _ in 0..50 {
+        if !profile.should_buy(&state, &mut rng) {
+            continue;
+        }
+        let choice = profile.choose_drink(&state.menu, &state.ingredients);
+        state.record_choice(&choice);
+        let DrinkChoice::Order(drink) = choice else {
+            continue;
+        };
+        assert_eq!(state.take_order(drink, 1), 1);
+        let feedback = profile.feedback(&state.menu.items[drink].recipe, &state.ingredients);
+        state.sell(drink, 1);
+        state.record_feedback(profile.segment, &feedback);
+        sold += 1;
+    }
+    state.end_day();
+
+    assert_eq!(sold, prepared);
+    assert_eq!(state.money, 300 + sold * 8);
+    assert!((state.reputation.standing(profile.segment) - (0.5 + 0.05 * sold as f32)).abs() < 1e-5);
+    assert_eq!(state.day, 2);
+    assert_eq!(state.menu.total_stock(), 0);
+
+    let report = state.ledger.day(1).unwrap();
+    assert_eq!(report.cups_prepared, prepared);
+    assert_eq!(report.cups_sold, sold);
+    assert_eq!(report.revenue(), sold * 8);
+    assert_eq!(report.love, sold);
+    assert_eq!(report.orders.get("Kopi Susu"), Some(&sold));
+    assert_eq!(report.total_walk_aways(), 0);
+    assert_eq!(report.wasted(PREPARED_CUP), (prepared - sold) as f32);
+    assert!(report.segment_change(profile.segment) > 0.0);
+    assert_eq!(report.segment_change(Segment::Senior), 0.0);
+}
+
+#[test]
+fn unsold_cups_are_wasted() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.record_purchase("milk", 1000.0, 30);
+    state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, None);
+
+    // Execute test
+    state.start_day();
+    state.take_order(0, 2);
+    state.sell(0, 2);
+    state.end_day();
+
+    let report = state.ledger.day(1).unwrap();
+    assert_eq!(report.cost_of_goods(), 30);
+    assert_eq!(report.profit(), 16 - 30);
+    assert_eq!(report.wasted(PREPARED_CUP), 6.0);
+    assert!(state.ledger.day(2).is_none());
+}
+
+#[test]
+fn orders_are_filled_partially() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, Some(3));
+    state.start_day();
+
+    // Execute test
+    assert_eq!(state.take_order(0, 2), 2);
+    assert_eq!(state.take_order(0, 4), 1);
+    assert_eq!(state.take_order(0, 1), 0);
+    assert_eq!(state.take_order(5, 1), 0);
+    assert_eq!(state.menu.total_stock(), 0);
+    assert_eq!(state.today().cups_short, 4);
+}
+
+#[test]
+fn abandoned_order_returns_stock() {
+    use crate::sim::reputation::STARTING_REPUTATION;
+
+    // Test data
+    let mut state = GameState::new_game();
+    state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, None);
+    state.start_day();
+    let prepared = state.menu.total_stock();
+
+    // Execute test
+    assert_eq!(state.take_order(0, 2), 2);
+    state.abandon_order(&[Segment::Student, Segment::Senior], 0, 2, 2);
+    assert_eq!(state.menu.total_stock(), prepared);
+    assert_eq!(state.today().abandoned, 2);
+    assert!(state.reputation.standing(Segment::Student) < STARTING_REPUTATION);
+    assert!(state.reputation.standing(Segment::Senior) < STARTING_REPUTATION);
+    assert_eq!(state.reputation.standing(Segment::OfficeWorker), STARTING_REPUTATION);
+    assert!(state.reputation.buzz < 0.0);
+}
+
+#[test]
+fn abandoned_partial_order_is_lost_once() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.prepare_drink(0, CoffeeComponent::from([("coffee", 7.0), ("milk", 120.0), ("sugar", 10.0)]), 8, Some(2));
+    state.start_day();
+
+    // Execute test
+    assert_eq!(state.take_order(0, 3), 2);
+    assert_eq!(state.today().cups_short, 1);
+    state.abandon_order(&[Segment::Student; 3], 0, 2, 3);
+    assert_eq!(state.menu.total_stock(), 2);
+    assert_eq!(state.today().cups_short, 0);
+    assert_eq!(state.today().abandoned, 3);
+    assert_eq!(state.today().total_lost_sales(), 3);
+}
+
+#[test]
+fn milk_spoils_overnight() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.day = 2;
+    state.stock_up("milk", 500.0);
+
+    // Execute test
+    state.end_day();
+    assert_eq!(state.inventory.total("milk"), 1500.0);
+
+    state.end_day();
+    assert_eq!(state.inventory.total("milk"), 500.0);
+    assert_eq!(state.ledger.day(3).unwrap().wasted("milk"), 1000.0);
+
+    state.end_day();
+    assert_eq!(state.inventory.total("milk"), 0.0);
+    assert_eq!(state.ledger.day(4).unwrap().wasted("milk"), 500.0);
+    assert_eq!(state.inventory.total("coffee"), 300.0);
+}
+
+#[test]
+fn buy_order() {
+    // Test data
+    let mut state = GameState::new_game();
+    let beans = Product::new("Coffee Beans 300 g", "coffee", 300.0, "g", 120);
+    let cups = Product::new("Paper Cups 50", CUP, 50.0, "cups", 50);
+    let premium = Product { unlock_day: 5, ..Product::new("Premium Arabica 1 kg", "coffee", 1000.0, "g", 360) };
+    let order = [(beans.clone(), 1), (cups, 10), (premium.clone(), 0)];
+
+    // Expected values
+    let expected_total = 120 + 450;
+
+    // Execute test
+    assert_eq!(state.quote_order(&order), Ok(expected_total));
+    assert!(state.buy_order(&[(beans, 5)]).is_err());
+    assert!(state.quote_order(&[(premium, 1)]).is_err());
+    assert!(state.quote_order(&[(Product::new("Ice 1 kg", "ice", 1000.0, "g", 10), 1)]).is_err());
+
+    state.money = 1000;
+    assert_eq!(state.buy_order(&order), Ok(expected_total));
+    assert_eq!(state.money, 1000 - expected_total);
+    assert_eq!(state.cup, 50 + 500);
+    assert_eq!(state.inventory.total("coffee"), 600.0);
+    assert_eq!(state.ledger.day(1).unwrap().cost_of_goods(), expected_total);
+}
+
+#[test]
+fn debt_leads_to_bankruptcy() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.money = 10;
+
+    // Execute test
+    for _ in 0..expenses::GRACE_DAYS {
+        state.pay_expenses();
+        state.end_day();
+        assert!(!state.is_bankrupt());
+    }
+    assert_eq!(state.grace_days_left(), 0);
+    assert!(state.ledger.day(1).unwrap().expenses() >= expenses::DAILY_RENT);
+
+    state.pay_expenses();
+    assert!(state.is_bankrupt());
+
+    state.money = 1000;
+    state.pay_expenses();
+    assert_eq!(state.days_in_debt, 0);
+}
+
+#[test]
+fn loan_schedule() {
+    // Test data
+    let mut state = GameState::new_game();
+
+    // Execute test
+    assert!(state.take_loan(state.available_credit() + 1).is_err());
+    state.take_loan(200).unwrap();
+    assert_eq!(state.money, 500);
+    assert_eq!(state.outstanding_debt(), 200);
+
+    for _ in 0..loan::LOAN_TERM_DAYS {
+        assert_eq!(state.service_loans(), 0);
+        state.end_day();
+    }
+    assert!(state.loans.is_empty());
+    assert!(state.money < 300);
+    assert_eq!(state.reputation, Reputation::default());
+}
+
+#[test]
+fn missed_payment_hurts_reputation() {
+    // Test data
+    let mut state = GameState::new_game();
+    state.take_loan(300).unwrap();
+    state.money = 0;
+
+    // Execute test
+    assert_eq!(state.service_loans(), 1);
+    assert_eq!(state.loans[0].missed_payments, 1);
+    assert_eq!(state.outstanding_debt(), 306 + loan::LATE_FEE);
+    assert!((state.reputation.overall() - (0.5 - loan::MISSED_PAYMENT_PENALTY)).abs() < 1e-6);
+
+    state.money = 1000;
+    assert_eq!(state.repay_loans(1000), 306 + loan::LATE_FEE);
+    assert!(state.loans.is_empty());
+}
+
+#[test]
+fn upgrades() {
+    use crate::sim::upgrade::Modifier;
+
+    // Test data
+    let mut state = GameState::new_game();
+    state.upgrade_tree = UpgradeTree {
+        upgrades: vec![
+            Upgrade::new("cooler", "Bigger Cooler", 150, None, vec![Modifier::bonus(Stat::StockCapacity, 20.0)]),
+            Upgrade::new("kettle", "Second Kettle", 200, Some("cooler"), vec![Modifier::multiplier(Stat::ServingSpeed, 1.5)]),
+        ],
+    };
+    let black = CoffeeComponent::from([("coffee", 1.0)]);
+    state.cup = 100;
+
+    // Execute test
+    assert_eq!(state.max_servings(0, &black), 40);
+    assert_eq!(state.buy_upgrade("kettle"), Err("Second Kettle needs the Bigger Cooler first".to_string()));
+    assert_eq!(state.buy_upgrade("cooler"), Ok(150));
+    assert_eq!(state.buy_upgrade("cooler"), Err("Bigger Cooler is already installed".to_string()));
+    assert_eq!(state.buy_upgrade("kettle"), Err("Not enough money".to_string()));
+    assert_eq!(state.money, 150);
+    assert_eq!(state.max_servings(0, &black), 60);
+    assert_eq!(state.modifiers().value(Stat::ServingSpeed), 1.0);
+
+    let report = state.ledger.day(state.day).unwrap();
+    assert_eq!(report.investments(), 150);
+    assert_eq!(report.profit(), 0);
+}
+
+#[test]
+fn staff() {
+    // Test data
+    let mut state = GameState::new_game();
+    let applicants = state.candidates();
+    let hired = applicants[0].clone();
+
+    // Execute test
+    assert_eq!(state.hire("Nobody"), Err("Nobody is not looking for work today".to_string()));
+    assert_eq!(state.hire(&hired.name), Ok(()));
+    assert_eq!(state.candidates().len(), applicants.len() - 1);
+    assert_eq!(state.workers().len(), 2);
+    assert_eq!(state.workers()[0].name, staff::OWNER);
+
+    state.record_serve(&hired.name, 2, &CustomerFeedback::Love);
+    let money = state.money;
+    state.pay_expenses();
+    let shift = state.ledger.shift_totals(&hired.name, hired.hired_on);
+    assert_eq!((shift.orders, shift.cups, shift.love, shift.wage), (1, 2, 1, hired.wage));
+    assert!(money - state.money >= hired.wage);
+
+    assert!(state.fire(&hired.name));
+    assert!(!state.fire(&hired.name));
+    assert_eq!(state.workers().len(), 1);
+}
+
+#[test]
+fn campaigns_run_out() {
+    use crate::sim::{campaign::Campaign, customer::Segment, upgrade::Modifier};
+
+    // Test data
+    let mut state = GameState::new_game();
+    let students = Stat::SegmentAttraction(Segment::Student);
+    state.campaign_list = CampaignList {
+        campaigns: vec![Campaign::new("social_post", "Social Media Post", 120, 5, vec![Modifier::multiplier(students, 1.8)])],
+    };
+
+    // Execute test
+    assert_eq!(state.launch_campaign("flyers"), Err("Unknown campaign flyers".to_string()));
+    assert_eq!(state.launch_campaign("social_post"), Ok(120));
+    assert_eq!(state.launch_campaign("social_post"), Err("Social Media Post is already running for 5 more day(s)".to_string()));
+    assert_eq!(state.money, 300 - 120);
+    assert_eq!(state.today().expenses(), 120);
+
+    let mut boosted_days = 0;
+    for _ in 0..7 {
+        if state.modifiers().value(students) > 1.0 {
+            boosted_days += 1;
+        }
+        state.end_day();
+    }
+    assert_eq!(boosted_days, 5);
+    assert!(state.campaigns.is_empty());
+}
+
+#[test]
+fn regulars() {
+    use crate::sim::{customer::Segment, regulars::Loyalty};
+
+    // Test data
+    let mut state = GameState::new_game();
+    let mut rng = SeededRng::new(2);
+    let variant = "res://resources/senior.tres";
+
+    // Execute test
+    assert_eq!(state.maybe_enlist(variant, Segment::Senior, CustomerFeedback::Like, &mut rng), None);
+    let name = (0..50)
+        .find_map(|_| state.maybe_enlist(variant, Segment::Senior, CustomerFeedback::Love, &mut rng))
+        .unwrap();
+    let enlisted = state.roster.regulars.len();
+    assert!(enlisted >= 1);
+
+    for _ in 0..3 {
+        state.end_day();
+        state.record_visit(&name, CustomerFeedback::Like);
+    }
+    state.record_visit("Nobody", CustomerFeedback::Like);
+    let regular = state.roster.get(&name).unwrap();
+    assert_eq!(regular.visits.len(), 4);
+    assert_eq!(regular.loyalty(), Loyalty::Regular);
+    assert_eq!(state.roster.regulars.len(), enlisted);
+}
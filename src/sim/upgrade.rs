@@ -0,0 +1,193 @@
+use crate::sim::customer::Segment;
+
+/// Something about the stand that upgrades, staff or campaigns can change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stat {
+    /// Cups served per second.
+    ServingSpeed,
+    /// Most cups the stand can hold prepared.
+    StockCapacity,
+    /// Scales how many passers-by walk up to the stand.
+    Attraction,
+    /// Scales how much a happy customer raises reputation.
+    ReputationGain,
+    /// Scales how many people of one segment walk up, on top of `Attraction`.
+    SegmentAttraction(Segment),
+}
+
+impl Stat {
+    /// Stats of the cart itself.
+    pub const CART: [Stat; 4] = [Stat::ServingSpeed, Stat::StockCapacity, Stat::Attraction, Stat::ReputationGain];
+    pub const ALL: [Stat; 7] = [
+        Stat::ServingSpeed,
+        Stat::StockCapacity,
+        Stat::Attraction,
+        Stat::ReputationGain,
+        Stat::SegmentAttraction(Segment::OfficeWorker),
+        Stat::SegmentAttraction(Segment::Student),
+        Stat::SegmentAttraction(Segment::Senior),
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Stat::ServingSpeed => "serving_speed",
+            Stat::StockCapacity => "stock_capacity",
+            Stat::Attraction => "attraction",
+            Stat::ReputationGain => "reputation_gain",
+            Stat::SegmentAttraction(Segment::OfficeWorker) => "attraction_office_worker",
+            Stat::SegmentAttraction(Segment::Student) => "attraction_student",
+            Stat::SegmentAttraction(Segment::Senior) => "attraction_senior",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Stat> {
+        Stat::ALL.into_iter().find(|stat| stat.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stat::ServingSpeed => "Serving speed",
+            Stat::StockCapacity => "Stock capacity",
+            Stat::Attraction => "Attraction",
+            Stat::ReputationGain => "Reputation gain",
+            Stat::SegmentAttraction(Segment::OfficeWorker) => "Office worker attraction",
+            Stat::SegmentAttraction(Segment::Student) => "Student attraction",
+            Stat::SegmentAttraction(Segment::Senior) => "Senior attraction",
+        }
+    }
+
+    /// Value of a bare cart.
+    pub fn base(&self) -> f32 {
+        match self {
+            Stat::ServingSpeed => 1.0,
+            Stat::StockCapacity => 40.0,
+            Stat::Attraction => 1.0,
+            Stat::ReputationGain => 1.0,
+            Stat::SegmentAttraction(_) => 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Effect {
+    /// Added to the base value.
+    Bonus(f32),
+    /// Scales the value after every bonus.
+    Multiplier(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Modifier {
+    pub stat: Stat,
+    pub effect: Effect,
+}
+
+impl Modifier {
+    pub const fn bonus(stat: Stat, amount: f32) -> Self {
+        Self { stat, effect: Effect::Bonus(amount) }
+    }
+
+    pub const fn multiplier(stat: Stat, factor: f32) -> Self {
+        Self { stat, effect: Effect::Multiplier(factor) }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.effect {
+            Effect::Bonus(amount) => format!("{} {:+}", self.stat.name(), amount),
+            Effect::Multiplier(factor) => format!("{} x{}", self.stat.name(), factor),
+        }
+    }
+}
+
+/// Every modifier active today. Values are `(base + bonuses) * multipliers`,
+/// so the order modifiers are added in doesn't matter.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Modifiers {
+    modifiers: Vec<Modifier>,
+}
+
+impl Modifiers {
+    pub fn add(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+    }
+
+    pub fn extend<'a>(&mut self, modifiers: impl IntoIterator<Item = &'a Modifier>) {
+        self.modifiers.extend(modifiers);
+    }
+
+    pub fn value(&self, stat: Stat) -> f32 {
+        let (bonus, factor) = self.modifiers
+            .iter()
+            .filter(|modifier| modifier.stat == stat)
+            .fold((0.0, 1.0), |(bonus, factor), modifier| match modifier.effect {
+                Effect::Bonus(amount) => (bonus + amount, factor),
+                Effect::Multiplier(multiplier) => (bonus, factor * multiplier),
+            });
+        (stat.base() + bonus) * factor
+    }
+}
+
+/// A one-off improvement to the cart, bought in the prep phase.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Upgrade {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub price: i32,
+    /// Upgrade that has to be owned first.
+    pub requires: Option<String>,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl Upgrade {
+    pub fn new(id: &str, name: &str, price: i32, requires: Option<&str>, modifiers: Vec<Modifier>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: String::new(),
+            price,
+            requires: requires.map(str::to_string),
+            modifiers,
+        }
+    }
+
+    /// "Serving speed x1.25, Attraction x1.1"
+    pub fn effects_text(&self) -> String {
+        self.modifiers.iter().map(Modifier::describe).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Every upgrade on offer, in display order. Defined by designers in an
+/// `UpgradeCatalog` resource.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UpgradeTree {
+    pub upgrades: Vec<Upgrade>,
+}
+
+impl UpgradeTree {
+    pub fn get(&self, id: &str) -> Option<&Upgrade> {
+        self.upgrades.iter().find(|upgrade| upgrade.id == id)
+    }
+
+    /// Modifiers of the `owned` upgrades, unknown ids are ignored.
+    pub fn modifiers<'a>(&'a self, owned: &'a [String]) -> impl Iterator<Item = &'a Modifier> {
+        owned.iter().filter_map(|id| self.get(id)).flat_map(|upgrade| upgrade.modifiers.iter())
+    }
+}
+
+#[test]
+fn modifiers_stack() {
+    // Test data
+    let mut modifiers = Modifiers::default();
+    modifiers.add(Modifier::multiplier(Stat::ServingSpeed, 1.5));
+    modifiers.add(Modifier::bonus(Stat::StockCapacity, 20.0));
+    modifiers.add(Modifier::multiplier(Stat::StockCapacity, 2.0));
+    modifiers.add(Modifier::bonus(Stat::StockCapacity, 10.0));
+
+    // Execute test
+    assert_eq!(Modifiers::default().value(Stat::ServingSpeed), 1.0);
+    assert_eq!(modifiers.value(Stat::ServingSpeed), 1.5);
+    assert_eq!(modifiers.value(Stat::StockCapacity), (40.0 + 30.0) * 2.0);
+    assert_eq!(modifiers.value(Stat::Attraction), 1.0);
+    assert_eq!(Modifier::bonus(Stat::StockCapacity, 30.0).describe(), "Stock capacity +30");
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::sim::{recipe::CoffeeComponent, rng::SimRng};
+
+/// Chance, in percent, that the morning forecast is right.
+pub const FORECAST_ACCURACY: f32 = 70.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Weather {
+    #[default]
+    Sunny,
+    Hot,
+    Rainy,
+    Stormy,
+}
+
+impl Weather {
+    pub const ALL: [Weather; 4] = [Weather::Sunny, Weather::Hot, Weather::Rainy, Weather::Stormy];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weather::Sunny => "Sunny",
+            Weather::Hot => "Hot",
+            Weather::Rainy => "Rainy",
+            Weather::Stormy => "Stormy",
+        }
+    }
+
+    /// Relative odds of the weather on any given day.
+    fn frequency(&self) -> f32 {
+        match self {
+            Weather::Sunny => 45.0,
+            Weather::Hot => 20.0,
+            Weather::Rainy => 25.0,
+            Weather::Stormy => 10.0,
+        }
+    }
+
+    /// Scales how many people walk past the stand.
+    pub fn traffic_multiplier(&self) -> f32 {
+        match self {
+            Weather::Sunny => 1.0,
+            Weather::Hot => 1.15,
+            Weather::Rainy => 0.7,
+            Weather::Stormy => 0.4,
+        }
+    }
+
+    /// Scales the chance that a passer-by stops.
+    pub fn buy_multiplier(&self) -> f32 {
+        match self {
+            Weather::Sunny => 1.0,
+            Weather::Hot => 1.1,
+            Weather::Rainy => 0.9,
+            Weather::Stormy => 0.8,
+        }
+    }
+
+    /// Added to every customer's preferences: iced and sweeter when it's hot,
+    /// stronger and milkier when it pours.
+    pub fn preference_shift(&self) -> CoffeeComponent {
+        match self {
+            Weather::Sunny => CoffeeComponent::default(),
+            Weather::Hot => CoffeeComponent::from([("ice", 80.0), ("sugar", 3.0)]),
+            Weather::Rainy => CoffeeComponent::from([("coffee", 1.0)]),
+            Weather::Stormy => CoffeeComponent::from([("coffee", 2.0), ("milk", 20.0)]),
+        }
+    }
+
+    pub fn roll(rng: &mut impl SimRng) -> Weather {
+        let frequencies = Weather::ALL.map(|weather| weather.frequency());
+        rng.pick_weighted(&frequencies).map_or(Weather::Sunny, |index| Weather::ALL[index])
+    }
+
+    /// What the morning forecast announces for a day with `actual` weather.
+    pub fn forecast(actual: Weather, rng: &mut impl SimRng) -> Weather {
+        if rng.check_chance(FORECAST_ACCURACY) {
+            return actual;
+        }
+        let others: Vec<Weather> = Weather::ALL.into_iter().filter(|weather| *weather != actual).collect();
+        others[rng.randi_range(0, others.len() as i32 - 1) as usize]
+    }
+}
+
+#[test]
+fn weather_is_seeded() {
+    use crate::sim::rng::SeededRng;
+
+    // Test data
+    let days = 1..=200;
+
+    // Execute test
+    let rolls: Vec<Weather> = days.clone().map(|day| Weather::roll(&mut SeededRng::for_day(5, day, 3))).collect();
+    let replay: Vec<Weather> = days.map(|day| Weather::roll(&mut SeededRng::for_day(5, day, 3))).collect();
+    assert_eq!(rolls, replay);
+    assert!(Weather::ALL.iter().all(|weather| rolls.contains(weather)));
+
+    let mut rng = SeededRng::new(9);
+    let right = (0..1000).filter(|_| Weather::forecast(Weather::Rainy, &mut rng) == Weather::Rainy).count();
+    assert!((600..800).contains(&right));
+}
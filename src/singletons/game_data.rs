@@ -1,49 +1,25 @@
-use godot::{classes::{file_access::ModeFlags, Engine, FileAccess}, global::clampf, prelude::*};
-use serde::{Deserialize, Serialize};
+use godot::{classes::{Engine, Os, RandomNumberGenerator, Texture2D, Time}, prelude::*};
 
-use crate::enums::customer_feedback::CustomerFeedback;
+use crate::{campaign_catalog::CampaignCatalog, enums::customer_feedback::CustomerFeedback, ingredient_catalog::IngredientCatalog, save::{integrity::SaveIntegrity, slot::{self, SlotInfo, SlotMeta}, GameDataSave, SaveError}, sim::{customer::{DrinkChoice, Segment}, recipe::CoffeeComponent, state::GameState}, singletons::save_storage::SaveStorage, upgrade_catalog::UpgradeCatalog};
 
-
-#[derive(Default, Serialize, Deserialize)]
-pub struct CoffeeComponent {
-    pub coffee: f32,
-    pub milk: f32,
-    pub sugar: f32,
-}
-
-impl CoffeeComponent {
-    pub fn _is_empty(&self) -> bool {
-        match (self.coffee, self.milk, self.sugar) {
-            (0.0, 0.0, 0.0) => true,
-            _ => false,
-        }
-    }
-}
-
-#[derive(Default, Serialize, Deserialize)]
-struct GameDataSave {
-    pub stock: i32,
-    pub money: i32,
-    pub day: i32,
-    pub price: i32,
-    pub cup: i32,
-    pub favorability: f32,
-    pub inventory: CoffeeComponent,
-    pub recipe: CoffeeComponent,
-}
+const ACCEPT_MODIFIED_SAVES_ARG: &str = "--accept-modified-saves";
+const INGREDIENTS_PATH: &str = "res://resources/ingredients.tres";
+const UPGRADES_PATH: &str = "res://resources/upgrades.tres";
+const CAMPAIGNS_PATH: &str = "res://resources/campaigns.tres";
 
 #[derive(GodotClass)]
 #[class(init, base=Object)]
 pub struct GameDataSingleton {
     base: Base<Object>,
-    pub stock: i32,
-    pub money: i32,
-    pub day: i32,
-    pub price: i32,
-    pub cup: i32,
-    pub favorability: f32,
-    pub inventory: CoffeeComponent,
-    pub recipe: CoffeeComponent,
+    pub state: GameState,
+    current_slot: Option<u32>,
+    slot_meta: SlotMeta,
+    session_started_msec: u64,
+    notice: Option<String>,
+    ingredient_catalog: Option<Gd<IngredientCatalog>>,
+    upgrade_catalog: Option<Gd<UpgradeCatalog>>,
+    campaign_catalog: Option<Gd<CampaignCatalog>>,
+    legacy_save_imported: bool,
 }
 
 #[godot_api]
@@ -53,161 +29,271 @@ impl GameDataSingleton {
     }
 
     pub fn start_new(&mut self) {
-        self.stock = 0;
-        self.money = 300;
-        self.day = 1;
-        self.inventory = CoffeeComponent {
-            coffee: 300.0,
-            milk: 1000.0,
-            sugar: 1000.0,
-        };
-        self.price = 8;
-        self.cup = 50;
-        self.favorability = 0.5;
+        let mut rng = RandomNumberGenerator::new_gd();
+        let seed = ((rng.randi() as u64) << 32) | rng.randi() as u64;
+        self.state.start_new(seed);
+        self.load_ingredients();
+        self.load_upgrades();
+        self.load_campaigns();
     }
 
     pub fn is_new_game(&self) -> bool {
-        self.day == 0
+        self.state.is_new_game()
     }
 
-    pub fn save_recipe(&mut self, coffee: f32, milk: f32, sugar: f32) {
-        self.recipe.coffee = coffee;
-        self.recipe.milk = milk;
-        self.recipe.sugar = sugar;
+    pub fn prepare_drink(&mut self, drink: usize, recipe: CoffeeComponent, price: i32, cups: Option<i32>) -> i32 {
+        self.state.prepare_drink(drink, recipe, price, cups)
+    }
 
-        let coffee_divided = self.inventory.coffee / self.recipe.coffee;
-        let milk_divided = self.inventory.milk / self.recipe.milk;
-        let sugar_divided = self.inventory.sugar / self.recipe.sugar;
+    pub fn start_day(&mut self) {
+        self.state.start_day();
+    }
 
-        let possible_cups = f32::min(coffee_divided, milk_divided);
-        let possible_cups = f32::min(possible_cups, sugar_divided);
+    pub fn record_choice(&mut self, choice: &DrinkChoice) {
+        self.state.record_choice(choice);
+    }
 
-        self.stock = possible_cups.floor() as i32;
+    pub fn record_feedback(&mut self, segment: Segment, feedback: &CustomerFeedback) {
+        self.state.record_feedback(segment, feedback);
     }
 
-    pub fn start_day(&mut self) {
-        // self.day += 1;
-        self.cup -= self.stock;
-        self.inventory.coffee -= self.recipe.coffee;
-        self.inventory.milk -= self.recipe.milk;
-        self.inventory.sugar -= self.recipe.sugar;
-    }
-
-    pub fn add_money(&mut self, amount: i32) -> i32 {
-        self.money += amount;
-        self.money
-    }
-
-    pub fn update_favorability(&mut self, feedback: &CustomerFeedback) {
-        self.favorability = match feedback {
-            CustomerFeedback::Love => clampf(self.favorability as f64 + 0.05, 0.0, 1.0),
-            CustomerFeedback::Like => clampf(self.favorability as f64 + 0.02, 0.0, 1.0),
-            CustomerFeedback::Dislike => clampf(self.favorability as f64 + 0.04, 0.0, 1.0),
-            _ => self.favorability as f64,
-        } as f32
-    }
-
-    pub fn save_game(&self) {
-        let file = FileAccess::open("user://savegame.json", ModeFlags::WRITE);
-        if let Some(mut file) = file {
-            let save_data = self.to_save();
-            let save_json = serde_json::to_string(&save_data);
-            if let Ok(json_string) = save_json {
-                file.store_string(&json_string);
-            }
+    /// Collects loan installments, charges the running costs and closes the
+    /// day. Warns the player about missed payments and debt.
+    pub fn end_day(&mut self) {
+        let missed_payments = self.state.service_loans();
+        self.state.pay_expenses();
+        self.state.end_day();
+
+        let mut warnings = Vec::new();
+        if missed_payments > 0 {
+            warnings.push(format!("You missed {} loan payment(s). The lender added a late fee and word got around.", missed_payments));
+        }
+        if self.state.days_in_debt > 0 && !self.state.is_bankrupt() {
+            warnings.push(match self.state.grace_days_left() {
+                0 => "You are in debt. Close another day below zero and the business goes bankrupt.".to_string(),
+                days => format!("You are in debt. You can close {} more day(s) below zero before the business goes bankrupt.", days),
+            });
+        }
+        if !warnings.is_empty() {
+            self.notice = Some(warnings.join("\n"));
+        }
+    }
+
+    /// Ends a bankrupt run. Its final save is archived and the slot freed.
+    pub fn game_over(&mut self) {
+        let Some(id) = self.current_slot.take() else {
+            return;
+        };
+
+        let now = Time::singleton().get_unix_time_from_system() as i64;
+        self.slot_meta.update_from(&self.state, now, 0.0);
+        let save = GameDataSave::new(self.state.clone(), self.slot_meta.clone());
+        if let Err(err) = SaveStorage::archive_slot(id, &save) {
+            godot_error!("Failed to archive slot {}: {}", id, err);
+        }
+    }
+
+    /// Starts a fresh run in the next free slot.
+    pub fn new_game(&mut self) {
+        let id = slot::next_free_slot(&SaveStorage::slot_ids());
+
+        self.state = GameState::default();
+        self.load_ingredients();
+        self.load_upgrades();
+        self.load_campaigns();
+        self.current_slot = Some(id);
+        self.slot_meta = SlotMeta::named(&format!("Run {}", id));
+        self.session_started_msec = Time::singleton().get_ticks_msec();
+    }
+
+    pub fn save_game(&mut self) {
+        let Some(id) = self.current_slot else {
+            godot_error!("Failed to save game: no save slot selected");
+            return;
         };
+
+        let now_msec = Time::singleton().get_ticks_msec();
+        let played_secs = now_msec.saturating_sub(self.session_started_msec) as f64 / 1000.0;
+        let now = Time::singleton().get_unix_time_from_system() as i64;
+        self.slot_meta.update_from(&self.state, now, played_secs);
+        self.session_started_msec = now_msec;
+
+        let save = GameDataSave::new(self.state.clone(), self.slot_meta.clone());
+        if let Err(err) = SaveStorage::write_slot(id, &save) {
+            godot_error!("Failed to save game: {}", err);
+        }
     }
 
+    /// Continues the most recently played slot.
     pub fn load_game(&mut self) {
-        let file = FileAccess::open("user://savegame.json", ModeFlags::READ);
-        if let Some(file) = file {
-            let json_string = file.get_as_text();
-            let save_data: Result<GameDataSave, _> = serde_json::from_str(json_string.to_string().as_str());
-            if let Ok(save) = save_data {
-                self.from_save(save);
-            }
+        let Some(latest) = self.list_slots().first().map(|slot| slot.id) else {
+            godot_error!("Failed to load game: {}", SaveError::Missing);
+            return;
         };
+        self.load_slot(latest);
+    }
+
+    pub fn load_slot(&mut self, id: u32) -> bool {
+        match SaveStorage::read_slot(id, Self::accepts_modified_saves()) {
+            Ok(loaded) => {
+                let reason = match loaded.integrity {
+                    SaveIntegrity::Modified => "was modified outside the game",
+                    _ => "was damaged",
+                };
+                if let Some(timestamp) = loaded.recovered_from {
+                    let backup_time = Time::singleton().get_datetime_string_from_unix_time_ex((timestamp / 1000) as i64)
+                        .use_space(true)
+                        .done();
+                    self.notice = Some(format!("Your save {}. Restored the backup from {}.", reason, backup_time));
+                } else if loaded.integrity == SaveIntegrity::Modified {
+                    self.notice = Some(format!("This save {}. It is now flagged as modified.", reason));
+                }
+                let save = loaded.into_save();
+                self.state = save.state;
+                self.load_ingredients();
+                self.load_upgrades();
+                self.load_campaigns();
+                self.slot_meta = save.meta;
+                self.current_slot = Some(id);
+                self.session_started_msec = Time::singleton().get_ticks_msec();
+                true
+            }
+            Err(err) => {
+                godot_error!("Failed to load slot {}: {}", id, err);
+                false
+            }
+        }
+    }
+
+    /// Replaces the built-in coffee, milk and sugar with the designers'
+    /// ingredient catalog.
+    fn load_ingredients(&mut self) {
+        if self.ingredient_catalog.is_none() {
+            match try_load::<IngredientCatalog>(INGREDIENTS_PATH) {
+                Ok(catalog) => self.ingredient_catalog = Some(catalog),
+                Err(err) => {
+                    godot_error!("Failed to load ingredients: {}", err);
+                    return;
+                }
+            }
+        }
+        if let Some(catalog) = &self.ingredient_catalog {
+            self.state.ingredients = catalog.bind().to_registry();
+        }
+    }
+
+    fn load_upgrades(&mut self) {
+        if self.upgrade_catalog.is_none() {
+            match try_load::<UpgradeCatalog>(UPGRADES_PATH) {
+                Ok(catalog) => self.upgrade_catalog = Some(catalog),
+                Err(err) => {
+                    godot_error!("Failed to load upgrades: {}", err);
+                    return;
+                }
+            }
+        }
+        if let Some(catalog) = &self.upgrade_catalog {
+            self.state.upgrade_tree = catalog.bind().to_tree();
+        }
+    }
+
+    fn load_campaigns(&mut self) {
+        if self.campaign_catalog.is_none() {
+            match try_load::<CampaignCatalog>(CAMPAIGNS_PATH) {
+                Ok(catalog) => self.campaign_catalog = Some(catalog),
+                Err(err) => {
+                    godot_error!("Failed to load campaigns: {}", err);
+                    return;
+                }
+            }
+        }
+        if let Some(catalog) = &self.campaign_catalog {
+            self.state.campaign_list = catalog.bind().to_list();
+        }
+    }
+
+    pub fn ingredient_icon(&self, id: &str) -> Option<Gd<Texture2D>> {
+        self.ingredient_catalog.as_ref()?.bind().icon(id)
+    }
+
+    /// Hand-edited saves are only loaded by debug builds started with
+    /// `-- --accept-modified-saves`.
+    fn accepts_modified_saves() -> bool {
+        let mut os = Os::singleton();
+        os.is_debug_build()
+            && os.get_cmdline_user_args()
+                .as_slice()
+                .iter()
+                .any(|arg| arg.to_string() == ACCEPT_MODIFIED_SAVES_ARG)
+    }
+
+    /// Message for the player about something that happened while loading, shown once.
+    pub fn take_notice(&mut self) -> Option<String> {
+        self.notice.take()
+    }
+
+    /// Moves a save from before slots existed into a slot. Runs once, at
+    /// startup before the slots are listed.
+    pub fn import_legacy_save(&mut self) {
+        if !self.legacy_save_imported {
+            SaveStorage::import_legacy_save();
+            self.legacy_save_imported = true;
+        }
     }
 
     pub fn is_save_exist(&self) -> bool {
-        let file = FileAccess::open("user://savegame.json", ModeFlags::READ);
-        if let Some(file) = file {
-            let json_string = file.get_as_text();
-            let save_data: Result<GameDataSave, _> = serde_json::from_str(json_string.to_string().as_str());
-            match save_data {
-                Ok(_) => return true,
-                Err(_) => return false,
+        !self.list_slots().is_empty()
+    }
+
+    /// Every readable slot, most recently played first.
+    pub fn list_slots(&self) -> Vec<SlotInfo> {
+        let mut slots: Vec<SlotInfo> = SaveStorage::slot_ids()
+            .into_iter()
+            .filter_map(|id| {
+                let loaded = SaveStorage::read_slot(id, Self::accepts_modified_saves()).ok()?;
+                Some(SlotInfo { id, meta: loaded.save.meta })
+            })
+            .collect();
+        slot::sort_slots(&mut slots);
+        slots
+    }
+
+    pub fn rename_slot(&mut self, id: u32, name: &str) {
+        if self.current_slot == Some(id) {
+            self.slot_meta.name = name.to_string();
+        }
+        let result = Self::rewrite_slot(id, id, |meta| meta.name = name.to_string());
+        if let Err(err) = result {
+            godot_error!("Failed to rename slot {}: {}", id, err);
+        }
+    }
+
+    pub fn duplicate_slot(&mut self, id: u32) -> Option<u32> {
+        let copy_id = slot::next_free_slot(&SaveStorage::slot_ids());
+        let result = Self::rewrite_slot(id, copy_id, |meta| meta.name = slot::copy_name(&meta.name));
+        match result {
+            Ok(_) => Some(copy_id),
+            Err(err) => {
+                godot_error!("Failed to duplicate slot {}: {}", id, err);
+                None
             }
-        };
+        }
+    }
 
-        false
-    }
-
-    fn to_save(&self) -> GameDataSave {
-        GameDataSave {
-            stock: self.stock,
-            money: self.money,
-            day: self.day,
-            price: self.price,
-            cup: self.cup,
-            favorability: self.favorability,
-            inventory: CoffeeComponent {
-                coffee: self.inventory.coffee,
-                milk: self.inventory.milk,
-                sugar: self.inventory.sugar,
-            },
-            recipe: CoffeeComponent {
-                coffee: self.recipe.coffee,
-                milk: self.recipe.milk,
-                sugar: self.recipe.sugar,
-            },
-        }
-    }
-
-    fn from_save(&mut self, save: GameDataSave) {
-        self.stock = save.stock;
-        self.money = save.money;
-        self.day = save.day;
-        self.price = save.price;
-        self.cup = save.cup;
-        self.favorability = save.favorability;
-        self.inventory = CoffeeComponent {
-            coffee: save.inventory.coffee,
-            milk: save.inventory.milk,
-            sugar: save.inventory.sugar,
-        };
-        self.recipe = CoffeeComponent {
-            coffee: save.recipe.coffee,
-            milk: save.recipe.milk,
-            sugar: save.recipe.sugar,
-        };
+    /// Reads slot `id`, edits its meta and writes it to slot `to`. A
+    /// modified save keeps its flag through the new seal.
+    fn rewrite_slot(id: u32, to: u32, edit: impl FnOnce(&mut SlotMeta)) -> Result<(), SaveError> {
+        let mut save = SaveStorage::read_slot(id, Self::accepts_modified_saves())?.into_save();
+        edit(&mut save.meta);
+        SaveStorage::write_slot(to, &save)
     }
-}
 
-#[test]
-fn save_recipe() {
-    // Test data
-    let inventory = CoffeeComponent {
-        coffee: 300.0, // grams
-        milk: 1000.0, // mL
-        sugar: 1000.0, // grams
-    };
-    let cup: i32 = 50;
-    
-    let coffee: f32 = 7.0;
-    let milk: f32 = 120.0;
-    let sugar: f32 = 10.0;
-
-    // Expected values
-    let expected_stock = 8;
-    let expected_cup = 42;
-
-    // Execute test
-    let mut game_data = GameDataSingleton::new_alloc();
-    game_data.bind_mut().inventory = inventory;
-    game_data.bind_mut().cup = cup;
-    game_data.bind_mut().save_recipe(coffee, milk, sugar);
-
-    assert_eq!(game_data.bind().cup, expected_cup);
-    assert_eq!(game_data.bind().stock, expected_stock);
-}
\ No newline at end of file
+    pub fn delete_slot(&mut self, id: u32) {
+        if self.current_slot == Some(id) {
+            self.current_slot = None;
+        }
+        if let Err(err) = SaveStorage::delete_slot(id) {
+            godot_error!("Failed to delete slot {}: {}", id, err);
+        }
+    }
+}
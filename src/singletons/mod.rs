@@ -1 +1,2 @@
-pub mod game_data;
\ No newline at end of file
+pub mod game_data;
+pub mod save_storage;
\ No newline at end of file
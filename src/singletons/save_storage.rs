@@ -0,0 +1,167 @@
+use godot::{classes::{file_access::ModeFlags, DirAccess, FileAccess, Time}, global::Error, prelude::*};
+
+use crate::save::{backup, integrity::SaveIntegrity, slot::{self, SlotMeta}, GameDataSave, SaveError};
+
+const SAVE_DIR: &str = "user://saves";
+const BACKUP_DIR: &str = "user://saves/backups";
+const ARCHIVE_DIR: &str = "user://saves/archive";
+const LEGACY_SAVE_PATH: &str = "user://savegame.json";
+
+/// A save read from disk, possibly restored from a backup.
+pub struct LoadedSave {
+    pub save: GameDataSave,
+    /// State of the slot file itself, before any backup was used.
+    pub integrity: SaveIntegrity,
+    /// Unix time in milliseconds of the backup used when the slot file was unreadable.
+    pub recovered_from: Option<u64>,
+}
+
+impl LoadedSave {
+    /// The save to write back to disk, still flagged if the slot file was
+    /// modified and loaded anyway. A restored backup is clean.
+    pub fn into_save(mut self) -> GameDataSave {
+        if self.recovered_from.is_none() {
+            self.save.keep_integrity(self.integrity);
+        }
+        self.save
+    }
+}
+
+/// File access for save slots under `user://saves`.
+pub struct SaveStorage;
+
+impl SaveStorage {
+    pub fn slot_ids() -> Vec<u32> {
+        DirAccess::get_files_at(SAVE_DIR)
+            .as_slice()
+            .iter()
+            .filter_map(|file_name| slot::slot_id_from_file_name(&file_name.to_string()))
+            .collect()
+    }
+
+    /// Reads a slot, falling back to the newest valid backup when the slot
+    /// file is corrupt or was modified and `accept_modified` isn't set.
+    pub fn read_slot(id: u32, accept_modified: bool) -> Result<LoadedSave, SaveError> {
+        let error = match Self::read_save(&Self::slot_path(id), accept_modified) {
+            Ok((save, integrity)) => return Ok(LoadedSave { save, integrity, recovered_from: None }),
+            Err(err) => err,
+        };
+
+        for file_name in backup::backups_of(id, &Self::backup_file_names()) {
+            let path = format!("{}/{}", BACKUP_DIR, file_name);
+            if let Ok((save, _)) = Self::read_save(&path, false) {
+                godot_warn!("Slot {} is unreadable ({}), restored {}", id, error, file_name);
+                let recovered_from = backup::parse_backup_file_name(&file_name).map(|(_, timestamp)| timestamp);
+                return Ok(LoadedSave { save, integrity: error.integrity(), recovered_from });
+            }
+        }
+
+        Err(error)
+    }
+
+    /// Writes a slot through a temporary file, keeping a backup of the previous save.
+    pub fn write_slot(id: u32, save: &GameDataSave) -> Result<(), SaveError> {
+        DirAccess::make_dir_recursive_absolute(BACKUP_DIR);
+        let path = Self::slot_path(id);
+        let temp_path = backup::temp_path(&path);
+
+        Self::write_file(&temp_path, &save.to_json()?)?;
+
+        if Self::read_save(&path, false).is_ok() {
+            let timestamp = (Time::singleton().get_unix_time_from_system() * 1000.0) as u64;
+            let backup_path = format!("{}/{}", BACKUP_DIR, backup::backup_file_name(id, timestamp));
+            DirAccess::copy_absolute(&path, &backup_path);
+        }
+
+        let error = DirAccess::rename_absolute(&temp_path, &path);
+        if error != Error::OK {
+            return Err(SaveError::Io(format!("{:?}", error)));
+        }
+
+        for file_name in backup::backups_to_prune(id, &Self::backup_file_names()) {
+            DirAccess::remove_absolute(&format!("{}/{}", BACKUP_DIR, file_name));
+        }
+        Ok(())
+    }
+
+    pub fn delete_slot(id: u32) -> Result<(), SaveError> {
+        for file_name in backup::backups_of(id, &Self::backup_file_names()) {
+            DirAccess::remove_absolute(&format!("{}/{}", BACKUP_DIR, file_name));
+        }
+
+        let error = DirAccess::remove_absolute(&Self::slot_path(id));
+        if error != Error::OK {
+            return Err(SaveError::Io(format!("{:?}", error)));
+        }
+        Ok(())
+    }
+
+    /// Stores the final save of a finished run under `user://saves/archive`
+    /// and frees its slot.
+    pub fn archive_slot(id: u32, save: &GameDataSave) -> Result<(), SaveError> {
+        DirAccess::make_dir_recursive_absolute(ARCHIVE_DIR);
+        let timestamp = (Time::singleton().get_unix_time_from_system() * 1000.0) as u64;
+        let path = format!("{}/{}", ARCHIVE_DIR, slot::archive_file_name(id, timestamp));
+        Self::write_file(&path, &save.to_json()?)?;
+
+        if FileAccess::file_exists(&Self::slot_path(id)) {
+            Self::delete_slot(id)?;
+        }
+        Ok(())
+    }
+
+    fn slot_path(id: u32) -> String {
+        format!("{}/{}", SAVE_DIR, slot::slot_file_name(id))
+    }
+
+    fn backup_file_names() -> Vec<String> {
+        DirAccess::get_files_at(BACKUP_DIR)
+            .as_slice()
+            .iter()
+            .map(|file_name| file_name.to_string())
+            .collect()
+    }
+
+    /// Moves the single pre-slot save file into the first free slot.
+    pub fn import_legacy_save() {
+        if !FileAccess::file_exists(LEGACY_SAVE_PATH) {
+            return;
+        }
+        let Ok((mut save, _)) = Self::read_save(LEGACY_SAVE_PATH, false) else {
+            return;
+        };
+
+        let id = slot::next_free_slot(&Self::slot_ids());
+        save.meta = SlotMeta::named(&format!("Run {}", id));
+        save.meta.update_from(&save.state, 0, 0.0);
+
+        if Self::write_slot(id, &save).is_ok() {
+            DirAccess::remove_absolute(LEGACY_SAVE_PATH);
+        }
+    }
+
+    fn write_file(path: &str, contents: &str) -> Result<(), SaveError> {
+        let mut file = FileAccess::open(path, ModeFlags::WRITE)
+            .ok_or_else(|| SaveError::Io(format!("{:?}", FileAccess::get_open_error())))?;
+        let stored = file.store_string(contents);
+        file.close();
+        if !stored {
+            return Err(SaveError::Io(format!("{:?}", file.get_error())));
+        }
+        Ok(())
+    }
+
+    fn read_save(path: &str, accept_modified: bool) -> Result<(GameDataSave, SaveIntegrity), SaveError> {
+        let file = FileAccess::open(path, ModeFlags::READ);
+        if let Some(file) = file {
+            let json_string = file.get_as_text();
+            let (save, integrity) = GameDataSave::read(json_string.to_string().as_str())?;
+            if integrity == SaveIntegrity::Modified && !accept_modified {
+                return Err(SaveError::Modified);
+            }
+            return Ok((save, integrity));
+        };
+
+        Err(SaveError::Missing)
+    }
+}
@@ -0,0 +1,65 @@
+use godot::{classes::{Button, HBoxContainer, IHBoxContainer, RichTextLabel}, prelude::*};
+
+use crate::sim::staff::{StaffMember, StaffShift};
+
+#[derive(GodotClass)]
+#[class(base=HBoxContainer)]
+pub struct StaffRow {
+    base: Base<HBoxContainer>,
+    name: String,
+
+    // Change or add your own properties here
+    #[export]
+    name_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    action_button: Option<Gd<Button>>,
+}
+
+#[godot_api]
+impl IHBoxContainer for StaffRow {
+    fn init(base: Base<HBoxContainer>) -> Self {
+        Self {
+            base,
+            name: String::new(),
+            name_label: None,
+            action_button: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let action_button = self.get_action_button().unwrap();
+        action_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_action_pressed);
+    }
+}
+
+#[godot_api]
+impl StaffRow {
+    #[signal]
+    pub fn action_requested(name: GString);
+
+    /// Shows a staff member with what they did so far, or an applicant
+    /// when there's no `record`.
+    pub fn set_member(&mut self, member: &StaffMember, record: Option<&StaffShift>, action: &str) {
+        self.name = member.name.clone();
+        let mut text = format!(
+            "[b]{}[/b] speed {:.0}% | skill {:.0}% | wage {}",
+            member.name,
+            member.speed * 100.0,
+            member.skill * 100.0,
+            member.wage,
+        );
+        if let Some(record) = record {
+            let satisfaction = record.satisfaction().map_or("-".to_string(), |share| format!("{:.0}%", share * 100.0));
+            text = format!("{}\n{} orders, {} cups, {} satisfied", text, record.orders, record.cups, satisfaction);
+        }
+        self.get_name_label().unwrap().set_text(&text);
+        self.get_action_button().unwrap().set_text(&format!("  {}  ", action));
+    }
+
+    fn on_action_pressed(&mut self) {
+        let name = GString::from(self.name.as_str());
+        self.signals().action_requested().emit(&name);
+    }
+}
@@ -0,0 +1,109 @@
+use godot::{classes::{Control, IControl, RichTextLabel, VBoxContainer}, prelude::*};
+
+use crate::{error_alert::ErrorAlert, get_node_by_abs_path, sim::staff::MAX_STAFF, singletons::game_data::GameDataSingleton, staff_row::StaffRow};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct StaffTab {
+    base: Base<Control>,
+    error_alert: Option<Gd<ErrorAlert>>,
+    rows: Vec<Gd<StaffRow>>,
+
+    // Change or add your own properties here
+    #[export]
+    row_scene: Option<Gd<PackedScene>>,
+    #[export]
+    team_container: Option<Gd<VBoxContainer>>,
+    #[export]
+    candidates_container: Option<Gd<VBoxContainer>>,
+    #[export]
+    team_label: Option<Gd<RichTextLabel>>,
+}
+
+#[godot_api]
+impl IControl for StaffTab {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            error_alert: None,
+            rows: Vec::new(),
+            row_scene: None,
+            team_container: None,
+            candidates_container: None,
+            team_label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
+        self.build_rows();
+    }
+}
+
+#[godot_api]
+impl StaffTab {
+    /// One row per staff member with a Fire button, then one per applicant
+    /// with a Hire button.
+    fn build_rows(&mut self) {
+        for mut row in self.rows.drain(..) {
+            row.queue_free();
+        }
+
+        let game_data = GameDataSingleton::get_instance();
+        let (staff, candidates, wages) = {
+            let state = &game_data.bind().state;
+            let staff: Vec<_> = state.staff
+                .iter()
+                .map(|member| (member.clone(), state.ledger.shift_totals(&member.name, member.hired_on)))
+                .collect();
+            (staff, state.candidates(), state.staff.iter().map(|member| member.wage).sum::<i32>())
+        };
+
+        self.get_team_label().unwrap().set_text(&format!(
+            "Staff {}/{} | Wages {} per day, paid at closing",
+            staff.len(),
+            MAX_STAFF,
+            wages,
+        ));
+
+        let row_scene = self.get_row_scene().unwrap();
+        let mut team_container = self.get_team_container().unwrap();
+        for (member, record) in staff.iter() {
+            let mut row = row_scene.instantiate_as::<StaffRow>();
+            team_container.add_child(&row);
+            row.bind_mut().set_member(member, Some(record), "Fire");
+            row.signals()
+                .action_requested()
+                .connect_other(&*self, Self::on_fire);
+            self.rows.push(row);
+        }
+
+        let mut candidates_container = self.get_candidates_container().unwrap();
+        for candidate in candidates.iter() {
+            let mut row = row_scene.instantiate_as::<StaffRow>();
+            candidates_container.add_child(&row);
+            row.bind_mut().set_member(candidate, None, "Hire");
+            row.signals()
+                .action_requested()
+                .connect_other(&*self, Self::on_hire);
+            self.rows.push(row);
+        }
+    }
+
+    fn on_hire(&mut self, name: GString) {
+        let mut game_data = GameDataSingleton::get_instance();
+        let result = game_data.bind_mut().state.hire(&name.to_string());
+        if let Err(message) = result {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from(&message));
+            return;
+        }
+        self.build_rows();
+    }
+
+    fn on_fire(&mut self, name: GString) {
+        let mut game_data = GameDataSingleton::get_instance();
+        game_data.bind_mut().state.fire(&name.to_string());
+        self.build_rows();
+    }
+}
@@ -0,0 +1,23 @@
+use godot::{classes::{IResource, Resource}, prelude::*};
+
+use crate::supplier_product::SupplierProduct;
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct SupplierCatalog {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    products: Array<Gd<SupplierProduct>>,
+}
+
+#[godot_api]
+impl IResource for SupplierCatalog {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            products: Array::new(),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use godot::{classes::{IResource, Resource, Texture2D}, prelude::*};
+
+use crate::sim::{catalog::Product, ingredient::IngredientRegistry, market::CUP};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct SupplierProduct {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    name: GString,
+    /// An ingredient id from the `IngredientCatalog`, or `cup`.
+    #[export]
+    ingredient: GString,
+    #[export]
+    quantity: f32,
+    #[export]
+    unit: GString,
+    #[export]
+    price: i32,
+    #[export]
+    unlock_day: i32,
+    #[export]
+    icon: Option<Gd<Texture2D>>,
+}
+
+#[godot_api]
+impl IResource for SupplierProduct {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            name: GString::new(),
+            ingredient: GString::from("coffee"),
+            quantity: 300.0,
+            unit: GString::from("g"),
+            price: 120,
+            unlock_day: 1,
+            icon: None,
+        }
+    }
+}
+
+impl SupplierProduct {
+    pub fn to_product(&self, ingredients: &IngredientRegistry) -> Option<Product> {
+        let good = self.ingredient.to_string();
+        if good != CUP && !ingredients.contains(&good) {
+            godot_error!("Supplier product {} has unknown ingredient `{}`", self.name, self.ingredient);
+            return None;
+        }
+        Some(Product {
+            name: self.name.to_string(),
+            good,
+            quantity: self.quantity,
+            unit: self.unit.to_string(),
+            price: self.price,
+            unlock_day: self.unlock_day,
+        })
+    }
+}
@@ -0,0 +1,33 @@
+use godot::{classes::{IResource, Resource}, prelude::*};
+
+use crate::{sim::upgrade::UpgradeTree, upgrade_definition::UpgradeDefinition};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct UpgradeCatalog {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    upgrades: Array<Gd<UpgradeDefinition>>,
+}
+
+#[godot_api]
+impl IResource for UpgradeCatalog {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            upgrades: Array::new(),
+        }
+    }
+}
+
+impl UpgradeCatalog {
+    pub fn to_tree(&self) -> UpgradeTree {
+        let upgrades = self.upgrades
+            .iter_shared()
+            .filter_map(|definition| definition.bind().to_upgrade())
+            .collect();
+        UpgradeTree { upgrades }
+    }
+}
@@ -0,0 +1,82 @@
+use godot::{classes::{IResource, Resource}, prelude::*};
+
+use crate::sim::upgrade::{Modifier, Stat, Upgrade};
+
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct UpgradeDefinition {
+    base: Base<Resource>,
+
+    // Change or add your own properties here
+    #[export]
+    id: GString,
+    #[export]
+    name: GString,
+    #[export(multiline)]
+    description: GString,
+    #[export]
+    price: i32,
+    /// Id of the upgrade that has to be owned first, empty for none.
+    #[export]
+    requires: GString,
+    /// Added to a stat by id, e.g. `{"stock_capacity": 30}`.
+    #[export]
+    bonuses: Dictionary,
+    /// Multiplies a stat by id, e.g. `{"serving_speed": 1.25}`.
+    #[export]
+    multipliers: Dictionary,
+}
+
+#[godot_api]
+impl IResource for UpgradeDefinition {
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            id: GString::new(),
+            name: GString::new(),
+            description: GString::new(),
+            price: 100,
+            requires: GString::new(),
+            bonuses: Dictionary::new(),
+            multipliers: Dictionary::new(),
+        }
+    }
+}
+
+impl UpgradeDefinition {
+    pub fn to_upgrade(&self) -> Option<Upgrade> {
+        if self.id.is_empty() {
+            godot_error!("Upgrade {} has no id", self.name);
+            return None;
+        }
+        let modifiers = to_modifiers(&format!("Upgrade {}", self.id), &self.bonuses, &self.multipliers)?;
+
+        let requires = Some(self.requires.to_string()).filter(|requires| !requires.is_empty());
+        let mut upgrade = Upgrade::new(&self.id.to_string(), &self.name.to_string(), self.price, requires.as_deref(), modifiers);
+        upgrade.description = self.description.to_string();
+        Some(upgrade)
+    }
+}
+
+/// Reads `{"stat_id": amount}` bonuses and multipliers. `owner` names the
+/// resource in error messages.
+pub fn to_modifiers(owner: &str, bonuses: &Dictionary, multipliers: &Dictionary) -> Option<Vec<Modifier>> {
+    let mut modifiers = Vec::new();
+    for (effects, to_modifier) in [
+        (bonuses, Modifier::bonus as fn(Stat, f32) -> Modifier),
+        (multipliers, Modifier::multiplier),
+    ] {
+        for (key, value) in effects.iter_shared() {
+            let Some(stat) = Stat::from_id(&key.to_string()) else {
+                godot_error!("{} changes unknown stat {}", owner, key);
+                return None;
+            };
+            let Ok(amount) = value.try_to::<f32>() else {
+                godot_error!("{} has a non-numeric {} effect", owner, key);
+                return None;
+            };
+            modifiers.push(to_modifier(stat, amount));
+        }
+    }
+    Some(modifiers)
+}
@@ -0,0 +1,71 @@
+use godot::{classes::{Button, HBoxContainer, IHBoxContainer, RichTextLabel}, prelude::*};
+
+use crate::sim::{state::GameState, upgrade::Upgrade};
+
+#[derive(GodotClass)]
+#[class(base=HBoxContainer)]
+pub struct UpgradeRow {
+    base: Base<HBoxContainer>,
+    upgrade: String,
+
+    // Change or add your own properties here
+    #[export]
+    name_label: Option<Gd<RichTextLabel>>,
+    #[export]
+    buy_button: Option<Gd<Button>>,
+}
+
+#[godot_api]
+impl IHBoxContainer for UpgradeRow {
+    fn init(base: Base<HBoxContainer>) -> Self {
+        Self {
+            base,
+            upgrade: String::new(),
+            name_label: None,
+            buy_button: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let buy_button = self.get_buy_button().unwrap();
+        buy_button.signals()
+            .pressed()
+            .connect_other(&*self, Self::on_buy_pressed);
+    }
+}
+
+#[godot_api]
+impl UpgradeRow {
+    #[signal]
+    pub fn buy_requested(id: GString);
+
+    pub fn set_upgrade(&mut self, upgrade: &Upgrade) {
+        self.upgrade = upgrade.id.clone();
+        let mut text = format!("[b]{}[/b] {}", upgrade.name, upgrade.effects_text());
+        if !upgrade.description.is_empty() {
+            text = format!("{}\n{}", text, upgrade.description);
+        }
+        self.get_name_label().unwrap().set_text(&text);
+    }
+
+    /// Shows whether the upgrade is owned, locked behind another one or for sale.
+    pub fn update(&mut self, state: &GameState) {
+        let Some(upgrade) = state.upgrade_tree.get(&self.upgrade) else {
+            return;
+        };
+        let required = upgrade.requires.as_deref().filter(|required| !state.owns_upgrade(required));
+        let (text, disabled) = match required {
+            _ if state.owns_upgrade(&upgrade.id) => ("  Owned  ".to_string(), true),
+            Some(required) => (format!("  Needs {}  ", state.upgrade_tree.get(required).map_or(required, |required| required.name.as_str())), true),
+            None => (format!("  Buy {}  ", upgrade.price), false),
+        };
+        let mut buy_button = self.get_buy_button().unwrap();
+        buy_button.set_text(&text);
+        buy_button.set_disabled(disabled);
+    }
+
+    fn on_buy_pressed(&mut self) {
+        let id = GString::from(self.upgrade.as_str());
+        self.signals().buy_requested().emit(&id);
+    }
+}
@@ -0,0 +1,96 @@
+use godot::{classes::{Control, IControl, RichTextLabel, VBoxContainer}, prelude::*};
+
+use crate::{error_alert::ErrorAlert, get_node_by_abs_path, sim::{state::GameState, upgrade::Stat}, singletons::game_data::GameDataSingleton, upgrade_row::UpgradeRow};
+
+#[derive(GodotClass)]
+#[class(base=Control)]
+pub struct UpgradesTab {
+    base: Base<Control>,
+    error_alert: Option<Gd<ErrorAlert>>,
+    rows: Vec<Gd<UpgradeRow>>,
+
+    // Change or add your own properties here
+    #[export]
+    row_scene: Option<Gd<PackedScene>>,
+    #[export]
+    row_container: Option<Gd<VBoxContainer>>,
+    #[export]
+    stats_label: Option<Gd<RichTextLabel>>,
+}
+
+#[godot_api]
+impl IControl for UpgradesTab {
+    fn init(base: Base<Control>) -> Self {
+        Self {
+            base,
+            error_alert: None,
+            rows: Vec::new(),
+            row_scene: None,
+            row_container: None,
+            stats_label: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        self.build_rows();
+        self.error_alert = Some(get_node_by_abs_path!(self.base(), "PrepPhase/ErrorAlert"));
+        self.update_rows();
+    }
+}
+
+#[godot_api]
+impl UpgradesTab {
+    #[signal]
+    pub fn on_upgrade_bought();
+
+    /// Adds one row per upgrade below the stats.
+    fn build_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let upgrades = game_data.bind().state.upgrade_tree.upgrades.clone();
+        let row_scene = self.get_row_scene().unwrap();
+        let mut row_container = self.get_row_container().unwrap();
+
+        for upgrade in upgrades.iter() {
+            let mut row = row_scene.instantiate_as::<UpgradeRow>();
+            row_container.add_child(&row);
+            row.bind_mut().set_upgrade(upgrade);
+            row.signals()
+                .buy_requested()
+                .connect_other(&*self, Self::on_buy);
+            self.rows.push(row);
+        }
+    }
+
+    fn on_buy(&mut self, id: GString) {
+        let mut game_data = GameDataSingleton::get_instance();
+        let result = game_data.bind_mut().state.buy_upgrade(&id.to_string());
+        if let Err(message) = result {
+            let error_alert = self.error_alert.as_mut().unwrap();
+            error_alert.bind_mut().show_alert(GString::from(&message));
+            return;
+        }
+        self.update_rows();
+        self.signals().on_upgrade_bought().emit();
+    }
+
+    fn update_rows(&mut self) {
+        let game_data = GameDataSingleton::get_instance();
+        let state = &game_data.bind().state;
+        for row in self.rows.iter_mut() {
+            row.bind_mut().update(state);
+        }
+        self.get_stats_label().unwrap().set_text(&Self::stats_text(state));
+    }
+
+    fn stats_text(state: &GameState) -> String {
+        let modifiers = state.modifiers();
+        Stat::CART
+            .iter()
+            .map(|stat| match stat {
+                Stat::StockCapacity => format!("{}: {} cups", stat.name(), state.stock_capacity()),
+                _ => format!("{}: x{:.2}", stat.name(), modifiers.value(*stat)),
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
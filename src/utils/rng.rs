@@ -1,4 +1,6 @@
-use godot::{classes::RandomNumberGenerator, obj::NewGd};
+use godot::{classes::RandomNumberGenerator, obj::{Gd, NewGd}};
+
+use crate::sim::rng::SimRng;
 
 pub fn coin_toss() -> i32 {
     let mut rng = RandomNumberGenerator::new_gd();
@@ -21,7 +23,31 @@ pub fn check_chance(percent: f32) -> bool {
     rng.randf() < (percent / 100.0)
 }
 
+#[allow(dead_code)]
 pub fn randi(from: i32, to: i32) -> i32 {
     let mut rng = RandomNumberGenerator::new_gd();
     rng.randi_range(from, to)
-}
\ No newline at end of file
+}
+
+/// Godot backed generator for the simulation core.
+pub struct GodotRng {
+    rng: Gd<RandomNumberGenerator>,
+}
+
+impl GodotRng {
+    pub fn new() -> Self {
+        Self { rng: RandomNumberGenerator::new_gd() }
+    }
+}
+
+impl Default for GodotRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimRng for GodotRng {
+    fn randf(&mut self) -> f32 {
+        self.rng.randf()
+    }
+}
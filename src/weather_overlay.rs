@@ -0,0 +1,47 @@
+use godot::{classes::{CpuParticles2D, ColorRect, IColorRect}, prelude::*};
+
+use crate::{sim::weather::Weather, singletons::game_data::GameDataSingleton};
+
+/// Tints the street and lets it rain according to today's weather.
+#[derive(GodotClass)]
+#[class(base=ColorRect)]
+pub struct WeatherOverlay {
+    base: Base<ColorRect>,
+
+    // Change or add your own properties here
+    #[export]
+    rain: Option<Gd<CpuParticles2D>>,
+}
+
+#[godot_api]
+impl IColorRect for WeatherOverlay {
+    fn init(base: Base<ColorRect>) -> Self {
+        Self {
+            base,
+            rain: None,
+        }
+    }
+
+    fn ready(&mut self) {
+        let weather = GameDataSingleton::get_instance().bind().state.weather;
+        self.show_weather(weather);
+    }
+}
+
+impl WeatherOverlay {
+    fn show_weather(&mut self, weather: Weather) {
+        let (tint, raindrops) = match weather {
+            Weather::Sunny => (Color::from_rgba(1.0, 1.0, 1.0, 0.0), 0),
+            Weather::Hot => (Color::from_rgba(1.0, 0.55, 0.1, 0.15), 0),
+            Weather::Rainy => (Color::from_rgba(0.3, 0.4, 0.6, 0.2), 80),
+            Weather::Stormy => (Color::from_rgba(0.1, 0.12, 0.25, 0.4), 200),
+        };
+        self.base_mut().set_color(tint);
+
+        let mut rain = self.get_rain().unwrap();
+        rain.set_emitting(raindrops > 0);
+        if raindrops > 0 {
+            rain.set_amount(raindrops);
+        }
+    }
+}